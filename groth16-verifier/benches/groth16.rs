@@ -0,0 +1,98 @@
+use ark_bn254::Bn254;
+use ark_groth16::Groth16;
+use criterion::{criterion_group, criterion_main, Criterion};
+use groth16_verifier::dummy_circuit::gen_groth16_dummy_circuit_proof;
+use groth16_verifier::Groth16Verifier;
+use on_proving_pairings::prover::PairingProver;
+use on_proving_pairings::setup::PairingPVKey;
+use on_proving_pairings::verifier::PairingVerifier;
+use std::ops::Neg;
+
+// Groth16 verifier: native, 4 pairings with e(α,-β), and 3 pairings with the target e(α,β).
+fn bench_groth16_verifier(c: &mut Criterion) {
+    let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<Bn254>(6);
+    let prepared_inputs = Groth16Verifier::prepare_inputs(&pvk, &pi).unwrap();
+    let target = Groth16Verifier::prepare_target(&pvk).unwrap();
+
+    let mut group = c.benchmark_group("groth16");
+    group.bench_function("native", |b| {
+        b.iter(|| {
+            Groth16::<Bn254>::verify_proof_with_prepared_inputs(&pvk, &proof, &prepared_inputs)
+        })
+    });
+    group.bench_function("4 pairings", |b| {
+        b.iter(|| {
+            Groth16Verifier::verify_proof_with_recursive_verifier(&pvk, &proof, &prepared_inputs)
+        })
+    });
+    group.bench_function("3 pairings with target", |b| {
+        b.iter(|| {
+            Groth16Verifier::verify_proof_with_target(&pvk, &target, &proof, &prepared_inputs)
+        })
+    });
+    group.bench_function("prepare_target", |b| {
+        b.iter(|| Groth16Verifier::prepare_target(&pvk))
+    });
+    group.finish();
+}
+
+// Only the pairing verifier part, namely the merged miller loop with (c, wi).
+fn bench_pairing_verifier(c: &mut Criterion) {
+    let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<Bn254>(6);
+    let prepared_inputs = Groth16Verifier::prepare_inputs(&pvk, &pi).unwrap().into();
+    let target = Groth16Verifier::prepare_target(&pvk).unwrap();
+
+    // 4 pairings: fixed [-γ,-δ,-β], non-fixed B
+    let pvk4 = PairingPVKey::setup(vec![
+        pvk.gamma_g2_neg_pc.clone(),
+        pvk.delta_g2_neg_pc.clone(),
+        pvk.vk.beta_g2.neg().into(),
+    ]);
+    let eval_points4 = vec![prepared_inputs, proof.c, pvk.vk.alpha_g1];
+    let proof4 =
+        PairingProver::prove(&pvk4, eval_points4.clone(), vec![proof.a], vec![proof.b]).unwrap();
+
+    // 3 pairings: fixed [-γ,-δ], non-fixed B, and the target e(α,β)
+    let pvk3 = PairingPVKey::setup(vec![
+        pvk.gamma_g2_neg_pc.clone(),
+        pvk.delta_g2_neg_pc.clone(),
+    ]);
+    let eval_points3 = vec![prepared_inputs, proof.c];
+    let proof3 = PairingProver::prove_with_target(
+        &pvk3,
+        eval_points3.clone(),
+        vec![proof.a],
+        vec![proof.b],
+        &target,
+    )
+    .unwrap();
+
+    let mut group = c.benchmark_group("pairing verifier");
+    group.bench_function("4 pairings", |b| {
+        b.iter(|| {
+            PairingVerifier::verify(
+                &pvk4,
+                eval_points4.clone(),
+                vec![proof.a],
+                vec![proof.b],
+                &proof4,
+            )
+        })
+    });
+    group.bench_function("3 pairings with target", |b| {
+        b.iter(|| {
+            PairingVerifier::verify_with_target(
+                &pvk3,
+                eval_points3.clone(),
+                vec![proof.a],
+                vec![proof.b],
+                &target,
+                &proof3,
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_groth16_verifier, bench_pairing_verifier);
+criterion_main!(benches);
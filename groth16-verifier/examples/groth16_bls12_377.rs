@@ -0,0 +1,26 @@
+// Verify a Groth16 proof over BLS12-377 with `on proving pairing`.
+//
+// cargo run --release -p groth16_verifier --example groth16_bls12_377
+use ark_bls12_377::Bls12_377;
+use groth16_verifier::bls12::Bls12Groth16Verifier;
+use groth16_verifier::dummy_circuit::gen_groth16_dummy_circuit_proof;
+use std::time::Instant;
+
+fn main() {
+    let k = 6;
+    let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<Bls12_377>(k);
+
+    let start = Instant::now();
+    let native =
+        Bls12Groth16Verifier::<ark_bls12_377::Config>::verify_proof(&pvk, &proof, &pi, false)
+            .unwrap();
+    println!("native verifier: {native}, {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let recursive =
+        Bls12Groth16Verifier::<ark_bls12_377::Config>::verify_proof(&pvk, &proof, &pi, true)
+            .unwrap();
+    println!("recursive verifier: {recursive}, {:?}", start.elapsed());
+
+    assert!(native && recursive);
+}
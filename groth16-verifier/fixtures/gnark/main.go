@@ -0,0 +1,130 @@
+// Generate the gnark fixtures of groth16-verifier over BN254:
+//
+//	verifying_key.bin, verifying_key_raw.bin: VerifyingKey.WriteTo and WriteRawTo
+//	proof.bin, proof_raw.bin: Proof.WriteTo and WriteRawTo
+//	public_witness.bin: the public witness.MarshalBinary
+//
+// and the same of CommitmentCircuit, which calls api.Commit, into commitment/.
+//
+// Run it in this directory with `go mod tidy && go run .`, which overwrites the fixtures.
+package main
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/consensys/gnark/backend/groth16"
+	"github.com/consensys/gnark/frontend"
+	"github.com/consensys/gnark/frontend/cs/r1cs"
+)
+
+// CubicCircuit checks x^3 + x + 5 == y, with the public y.
+type CubicCircuit struct {
+	X frontend.Variable
+	Y frontend.Variable `gnark:",public"`
+}
+
+func (c *CubicCircuit) Define(api frontend.API) error {
+	x3 := api.Mul(c.X, c.X, c.X)
+	api.AssertIsEqual(c.Y, api.Add(x3, c.X, 5))
+	return nil
+}
+
+// CommitmentCircuit checks x^2 == y, with the public x, and commits to x and y with one Pedersen commitment.
+// The hash of the commitment extends the public inputs, and it commits the public input 1, namely x.
+type CommitmentCircuit struct {
+	X frontend.Variable `gnark:",public"`
+	Y frontend.Variable
+}
+
+func (c *CommitmentCircuit) Define(api frontend.API) error {
+	committer, ok := api.(frontend.Committer)
+	if !ok {
+		return fmt.Errorf("the builder doesn't support api.Commit")
+	}
+	commitment, err := committer.Commit(c.X, c.Y)
+	if err != nil {
+		return err
+	}
+	api.AssertIsDifferent(commitment, 0)
+	api.AssertIsEqual(api.Mul(c.X, c.X), c.Y)
+	return nil
+}
+
+func main() {
+	if err := generate(".", &CubicCircuit{}, &CubicCircuit{X: 3, Y: 35}); err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		os.Exit(1)
+	}
+	if err := generate("commitment", &CommitmentCircuit{}, &CommitmentCircuit{X: 3, Y: 9}); err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		os.Exit(1)
+	}
+}
+
+// generate compiles the circuit, proves the assignment, verifies it, and writes the fixtures into dir.
+func generate(dir string, circuit, assignment frontend.Circuit) error {
+	ccs, err := frontend.Compile(ecc.BN254.ScalarField(), r1cs.NewBuilder, circuit)
+	if err != nil {
+		return err
+	}
+	pk, vk, err := groth16.Setup(ccs)
+	if err != nil {
+		return err
+	}
+	witness, err := frontend.NewWitness(assignment, ecc.BN254.ScalarField())
+	if err != nil {
+		return err
+	}
+	publicWitness, err := witness.Public()
+	if err != nil {
+		return err
+	}
+	proof, err := groth16.Prove(ccs, pk, witness)
+	if err != nil {
+		return err
+	}
+	if err := groth16.Verify(proof, vk, publicWitness); err != nil {
+		return err
+	}
+
+	publicWitnessBytes, err := publicWitness.MarshalBinary()
+	if err != nil {
+		return err
+	}
+	if err := os.MkdirAll(dir, 0o755); err != nil {
+		return err
+	}
+	files := []struct {
+		name  string
+		write func(io.Writer) (int64, error)
+	}{
+		{"verifying_key.bin", vk.WriteTo},
+		{"verifying_key_raw.bin", vk.WriteRawTo},
+		{"proof.bin", proof.WriteTo},
+		{"proof_raw.bin", proof.WriteRawTo},
+		{"public_witness.bin", func(w io.Writer) (int64, error) {
+			n, err := w.Write(publicWitnessBytes)
+			return int64(n), err
+		}},
+	}
+	for _, file := range files {
+		if err := writeFile(filepath.Join(dir, file.name), file.write); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func writeFile(path string, write func(io.Writer) (int64, error)) error {
+	f, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	defer f.Close()
+	_, err = write(f)
+	return err
+}
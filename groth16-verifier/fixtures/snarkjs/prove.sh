@@ -0,0 +1,14 @@
+#!/bin/sh
+# Regenerate verification_key.json, proof.json and public.json of mycircuit.circom with snarkjs.
+set -e
+
+# mycircuit.r1cs and mycircuit.wasm are compiled by circom 1, and mycircuit.zkey is the phase 2 key over
+# powersOfTau28_hez_final_10.ptau of the Hermez ceremony, without any contribution:
+#  circom mycircuit.circom --r1cs --wasm
+#  snarkjs zkey new mycircuit.r1cs powersOfTau28_hez_final_10.ptau mycircuit.zkey
+snarkjs zkey export verificationkey mycircuit.zkey verification_key.json
+
+echo '{"a": 3, "b": 11}' > input.json
+snarkjs wtns calculate mycircuit.wasm input.json witness.wtns
+snarkjs groth16 prove mycircuit.zkey witness.wtns proof.json public.json
+snarkjs groth16 verify verification_key.json public.json proof.json
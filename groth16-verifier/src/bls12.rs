@@ -0,0 +1,128 @@
+// Groth16 verifier over BLS12 curves, e.g. BLS12-377, with `on proving pairing`.
+//
+// The same as [`crate::Groth16Verifier`], but the pairing is proved and verified with
+// the BLS12 backend, where lambda = p - x.
+use ark_ec::bls12::{Bls12, G2Prepared};
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof};
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use on_proving_pairings::bls12::params::Bls12ResidueConfig;
+use on_proving_pairings::bls12::prover::PairingProver;
+use on_proving_pairings::bls12::setup::PairingPVKey;
+use on_proving_pairings::bls12::verifier::PairingVerifier;
+use on_proving_pairings::error::PairingError;
+use std::marker::PhantomData;
+
+type G1Projective<P> = <Bls12<P> as Pairing>::G1;
+type Fr<P> = <Bls12<P> as Pairing>::ScalarField;
+
+pub struct Bls12Groth16Verifier<P: Bls12ResidueConfig>(PhantomData<P>);
+
+impl<P: Bls12ResidueConfig> Bls12Groth16Verifier<P> {
+    // Params:
+    //  @is_recursive_verifier:
+    //       if true, will leverage power of `prove and verify pairing`.
+    //       if false, will do as same as `Groth16::verify_proof`.
+    pub fn verify_proof(
+        pvk: &PreparedVerifyingKey<Bls12<P>>,
+        proof: &Proof<Bls12<P>>,
+        public_inputs: &[Fr<P>],
+        is_recursive_verifier: bool,
+    ) -> R1CSResult<bool> {
+        let prepared_inputs = Self::prepare_inputs(pvk, public_inputs)?;
+        if is_recursive_verifier {
+            Self::verify_proof_with_recursive_verifier(pvk, proof, &prepared_inputs)
+        } else {
+            Groth16::<Bls12<P>>::verify_proof_with_prepared_inputs(pvk, proof, &prepared_inputs)
+        }
+    }
+
+    // Porting from `ark_groth16::Groth16::prepare_inputs`
+    pub fn prepare_inputs(
+        pvk: &PreparedVerifyingKey<Bls12<P>>,
+        public_inputs: &[Fr<P>],
+    ) -> R1CSResult<G1Projective<P>> {
+        if (public_inputs.len() + 1) != pvk.vk.gamma_abc_g1.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        let g_ic = pvk.vk.gamma_abc_g1[0].into_group();
+
+        let g_ic = g_ic + G1Projective::<P>::msm(&pvk.vk.gamma_abc_g1[1..], public_inputs).unwrap();
+
+        Ok(g_ic)
+    }
+
+    // Porting from `ark_groth16::Groth16::verify_proof_with_prepared_inputs`
+    pub fn verify_proof_with_recursive_verifier(
+        pvk: &PreparedVerifyingKey<Bls12<P>>,
+        proof: &Proof<Bls12<P>>,
+        prepared_inputs: &G1Projective<P>,
+    ) -> R1CSResult<bool> {
+        // setup: precompute lines for fixed [Q1,Q2,Q3]. proof.b(Q4) is non-fixed.
+        let pairing_pvk = PairingPVKey::<P>::setup(vec![
+            pvk.gamma_g2_neg_pc.clone(),
+            pvk.delta_g2_neg_pc.clone(),
+            G2Prepared::from(-pvk.vk.beta_g2),
+        ]);
+
+        // eval_points: [P1,P2,P3]
+        let eval_points = vec![prepared_inputs.into_affine(), proof.c, pvk.vk.alpha_g1];
+
+        // prove: finding_c
+        let pairing_proof = match PairingProver::<P>::prove(
+            &pairing_pvk,
+            eval_points.clone(),
+            vec![proof.a],
+            vec![proof.b],
+        ) {
+            Ok(pairing_proof) => pairing_proof,
+            // the pairing product is not 1, namely the groth16 proof is invalid.
+            Err(PairingError::NotRthResidue) => return Ok(false),
+            Err(_) => return Err(SynthesisError::Unsatisfiable),
+        };
+
+        // verify with the pairing proof (c, wi), without the miller loop output f.
+        PairingVerifier::<P>::verify(
+            &pairing_pvk,
+            eval_points,
+            vec![proof.a],
+            vec![proof.b],
+            &pairing_proof,
+        )
+        .map_err(|_| SynthesisError::Unsatisfiable)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dummy_circuit::gen_groth16_dummy_circuit_proof;
+    use ark_bls12_377::Bls12_377;
+
+    type Verifier = Bls12Groth16Verifier<ark_bls12_377::Config>;
+
+    #[test]
+    fn test_groth16_verifier_bls12_377() {
+        let k = 6;
+        let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<Bls12_377>(k);
+
+        assert!(Groth16::<Bls12_377>::verify_proof(&pvk, &proof, &pi).unwrap());
+        assert!(Verifier::verify_proof(&pvk, &proof, &pi, false).unwrap());
+        assert!(Verifier::verify_proof(&pvk, &proof, &pi, true).unwrap());
+    }
+
+    #[test]
+    fn test_groth16_verifier_bls12_377_with_invalid_proof() {
+        let k = 6;
+        let (mut proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<Bls12_377>(k);
+        proof.a = (proof.a + proof.a).into();
+
+        assert!(!Groth16::<Bls12_377>::verify_proof(&pvk, &proof, &pi).unwrap());
+        assert!(!Verifier::verify_proof(&pvk, &proof, &pi, false).unwrap());
+        assert!(!Verifier::verify_proof(&pvk, &proof, &pi, true).unwrap());
+
+        // wrong length of public inputs
+        assert!(Verifier::verify_proof(&pvk, &proof, &[], true).is_err());
+    }
+}
@@ -61,9 +61,34 @@ pub fn gen_groth16_dummy_circuit_proof<E: Pairing>(
     ark_groth16::PreparedVerifyingKey<E>,
     Vec<E::ScalarField>,
 ) {
-    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let (mut proofs, pvk, mut pis) = gen_groth16_dummy_circuit_proofs::<E>(k, 1);
+    (proofs.remove(0), pvk, pis.remove(0))
+}
+
+pub type DummyProofs<E> = (
+    Vec<ark_groth16::Proof<E>>,
+    ark_groth16::PreparedVerifyingKey<E>,
+    Vec<Vec<<E as Pairing>::ScalarField>>,
+);
+
+// Gen n groth16-verifier proofs over the same vk, each with its own witness (a, b), so the public
+// inputs c = a * b differ.
+//
+// return: (proofs, pvk, public_inputs of each proof)
+pub fn gen_groth16_dummy_circuit_proofs<E: Pairing>(k: usize, n: usize) -> DummyProofs<E> {
+    gen_groth16_dummy_circuit_proofs_with_seed::<E>(k, n, test_rng().next_u64())
+}
 
-    let circuit = DummyCircuit::<E::ScalarField> {
+// Same as `gen_groth16_dummy_circuit_proofs`, but the setup randomness comes from the seed, so that
+// different seeds give unrelated vks, which share none of their G2 points.
+pub fn gen_groth16_dummy_circuit_proofs_with_seed<E: Pairing>(
+    k: usize,
+    n: usize,
+    seed: u64,
+) -> DummyProofs<E> {
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(seed);
+
+    let mut circuit = DummyCircuit::<E::ScalarField> {
         a: Some(E::ScalarField::rand(&mut rng)),
         b: Some(E::ScalarField::rand(&mut rng)),
         num_variables: 10,
@@ -73,14 +98,21 @@ pub fn gen_groth16_dummy_circuit_proof<E: Pairing>(
     let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
     let pvk = prepare_verifying_key::<E>(&vk);
 
-    let c = circuit.a.unwrap() * circuit.b.unwrap();
-
-    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    let mut proofs = vec![];
+    let mut pis = vec![];
+    for i in 0..n {
+        if i > 0 {
+            circuit.a = Some(E::ScalarField::rand(&mut rng));
+            circuit.b = Some(E::ScalarField::rand(&mut rng));
+        }
+        let c = circuit.a.unwrap() * circuit.b.unwrap();
 
-    // public inputs
-    let pi = vec![c];
+        proofs.push(Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap());
+        // public inputs
+        pis.push(vec![c]);
+    }
 
-    (proof, pvk, pi)
+    (proofs, pvk, pis)
 }
 
 #[cfg(test)]
@@ -0,0 +1,686 @@
+// Import the Groth16 verifying key and proof of gnark over BN254, in its binary encoding.
+//
+// The points are in big-endian, with the flag in the 2 most significant bits of the first byte:
+//  0b00: uncompressed, G1: X|Y, G2: X.A1|X.A0|Y.A1|Y.A0, and the infinity is all zeros.
+//  0b10: compressed with the smallest Y, G1: X, G2: X.A1|X.A0
+//  0b11: compressed with the lexicographically largest Y
+//  0b01: compressed infinity
+// The slices are prefixed with their length in uint32.
+//
+// VerifyingKey: [α]1,[β]1,[β]2,[γ]2,[δ]1,[δ]2, uint32(len(K)),[K]1, PublicAndCommitmentCommitted([][]uint64),
+//               uint32(len(CommitmentKeys)), [G,GSigmaNeg]2 of each pedersen commitment key.
+// Proof: [Ar]1,[Bs]2,[Krs]1, uint32(len(Commitments)),[Commitments]1, [CommitmentPok]1
+// Public witness: uint32(nbPublic), uint32(nbSecret), uint32(len), [Fr] in 32 bytes each.
+//
+// With the commitments, the hash of each commitment Dj and its committed public inputs extends the public inputs:
+//  hj = hash_to_field(Dj|a_{i1}|a_{i2}|..., "bsb22-commitment")
+// and the challenge of the batched proof of knowledge is r = hash_to_field(h1|h2|..., "G16-BSB22").
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::bn::G2Prepared;
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_groth16::{prepare_verifying_key, PreparedVerifyingKey, Proof, VerifyingKey};
+use num_bigint::BigUint;
+use on_proving_pairings::setup::PairingPVKey;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+const FQ_BYTES: usize = 32;
+const FR_BYTES: usize = 32;
+
+const FLAG_MASK: u8 = 0b11 << 6;
+const FLAG_UNCOMPRESSED: u8 = 0b00 << 6;
+const FLAG_COMPRESSED_INFINITY: u8 = 0b01 << 6;
+const FLAG_COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const FLAG_COMPRESSED_LARGEST: u8 = 0b11 << 6;
+
+const COMMITMENT_DST: &[u8] = b"bsb22-commitment";
+const CHALLENGE_DST: &[u8] = b"G16-BSB22";
+
+// Errors of reading the gnark binary encoding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GnarkError {
+    UnexpectedEof,
+    TrailingBytes,
+    // The coordinate isn't less than the modulus.
+    InvalidField,
+    // The point isn't on the curve or in the subgroup.
+    InvalidPoint(&'static str),
+    // The lengths of K, commitments or public inputs mismatch.
+    WrongLength(&'static str),
+    // The commitment keys don't share GSigmaNeg, or the committed public input is out of range.
+    InvalidCommitmentKey,
+}
+
+impl fmt::Display for GnarkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GnarkError::UnexpectedEof => write!(f, "unexpected end of bytes"),
+            GnarkError::TrailingBytes => write!(f, "trailing bytes after decoding"),
+            GnarkError::InvalidField => write!(f, "the field element is not less than the modulus"),
+            GnarkError::InvalidPoint(name) => write!(f, "{} is not a valid point", name),
+            GnarkError::WrongLength(name) => write!(f, "the length of {} mismatches", name),
+            GnarkError::InvalidCommitmentKey => write!(f, "the commitment key is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for GnarkError {}
+
+// The verifying key of the pedersen commitment: e(D,G) * e(PoK,GSigmaNeg) = 1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PedersenVerifyingKey {
+    pub g: G2Affine,
+    pub g_sigma_neg: G2Affine,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GnarkVerifyingKey {
+    pub alpha_g1: G1Affine,
+    pub beta_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub gamma_g2: G2Affine,
+    pub delta_g1: G1Affine,
+    pub delta_g2: G2Affine,
+    // [K]1 for 1, the public inputs and the hashes of the commitments.
+    pub k: Vec<G1Affine>,
+    // the indices of the public inputs committed by each commitment, starting from 1.
+    pub public_and_commitment_committed: Vec<Vec<u64>>,
+    pub commitment_keys: Vec<PedersenVerifyingKey>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GnarkProof {
+    pub ar: G1Affine,
+    pub bs: G2Affine,
+    pub krs: G1Affine,
+    // the pedersen commitments D and the proof of knowledge of them
+    pub commitments: Vec<G1Affine>,
+    pub commitment_pok: G1Affine,
+}
+
+impl GnarkVerifyingKey {
+    // Read the verifying key written by `WriteTo` or `WriteRawTo` of gnark.
+    pub fn read(bytes: &[u8]) -> Result<Self, GnarkError> {
+        let mut decoder = Decoder::new(bytes);
+        let vk = Self {
+            alpha_g1: decoder.read_g1("alpha_g1")?,
+            beta_g1: decoder.read_g1("beta_g1")?,
+            beta_g2: decoder.read_g2("beta_g2")?,
+            gamma_g2: decoder.read_g2("gamma_g2")?,
+            delta_g1: decoder.read_g1("delta_g1")?,
+            delta_g2: decoder.read_g2("delta_g2")?,
+            k: decoder.read_g1_vec("k")?,
+            public_and_commitment_committed: decoder.read_u64_vec_vec()?,
+            commitment_keys: (0..decoder.read_u32()?)
+                .map(|_| {
+                    Ok(PedersenVerifyingKey {
+                        g: decoder.read_g2("commitment_key")?,
+                        g_sigma_neg: decoder.read_g2("commitment_key")?,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        decoder.finish()?;
+
+        if vk.public_and_commitment_committed.len() != vk.commitment_keys.len() {
+            return Err(GnarkError::WrongLength("commitment_keys"));
+        }
+        if vk.k.len() < 1 + vk.commitment_keys.len() {
+            return Err(GnarkError::WrongLength("k"));
+        }
+        Ok(vk)
+    }
+
+    // The number of public inputs, excluding the hashes of the commitments.
+    pub fn num_public_inputs(&self) -> usize {
+        self.k.len() - 1 - self.commitment_keys.len()
+    }
+
+    // Convert into the verifying key of ark, where K maps to gamma_abc_g1.
+    //
+    // For the proofs with commitments, the public inputs should be extended with the hashes of the commitments,
+    // and the commitments should be added into the prepared inputs.
+    pub fn to_verifying_key(&self) -> VerifyingKey<Bn254> {
+        VerifyingKey {
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            gamma_g2: self.gamma_g2,
+            delta_g2: self.delta_g2,
+            gamma_abc_g1: self.k.clone(),
+        }
+    }
+
+    pub fn to_prepared_verifying_key(&self) -> PreparedVerifyingKey<Bn254> {
+        prepare_verifying_key(&self.to_verifying_key())
+    }
+}
+
+impl GnarkProof {
+    // Read the proof written by `WriteTo` or `WriteRawTo` of gnark.
+    pub fn read(bytes: &[u8]) -> Result<Self, GnarkError> {
+        let mut decoder = Decoder::new(bytes);
+        let proof = Self {
+            ar: decoder.read_g1("ar")?,
+            bs: decoder.read_g2("bs")?,
+            krs: decoder.read_g1("krs")?,
+            commitments: decoder.read_g1_vec("commitments")?,
+            commitment_pok: decoder.read_g1("commitment_pok")?,
+        };
+        decoder.finish()?;
+        Ok(proof)
+    }
+
+    // Convert into the proof of ark, which drops the commitments.
+    pub fn to_proof(&self) -> Proof<Bn254> {
+        Proof {
+            a: self.ar,
+            b: self.bs,
+            c: self.krs,
+        }
+    }
+}
+
+// The verifying key of Groth16 with the commitments, which caches the miller lines of the fixed Qi.
+pub struct CommitmentPreparedVerifyingKey {
+    pub vk: GnarkVerifyingKey,
+    pub pvk: PreparedVerifyingKey<Bn254>,
+    // lines for the fixed [-γ,-δ,-β,G1,...,Gm,GSigmaNeg]
+    pub pairing_pvk: PairingPVKey<ark_bn254::Config>,
+}
+
+impl CommitmentPreparedVerifyingKey {
+    pub fn new(vk: GnarkVerifyingKey) -> Result<Self, GnarkError> {
+        // the batched proof of knowledge is checked against one GSigmaNeg.
+        let g_sigma_neg = match vk.commitment_keys.first() {
+            Some(key) => key.g_sigma_neg,
+            None => return Err(GnarkError::InvalidCommitmentKey),
+        };
+        if vk
+            .commitment_keys
+            .iter()
+            .any(|key| key.g_sigma_neg != g_sigma_neg)
+        {
+            return Err(GnarkError::InvalidCommitmentKey);
+        }
+        let num_public_inputs = vk.num_public_inputs() as u64;
+        if vk
+            .public_and_commitment_committed
+            .iter()
+            .flatten()
+            .any(|i| *i == 0 || *i > num_public_inputs)
+        {
+            return Err(GnarkError::InvalidCommitmentKey);
+        }
+
+        let pvk = vk.to_prepared_verifying_key();
+        let pairing_pvk = PairingPVKey::setup(
+            [
+                pvk.gamma_g2_neg_pc.clone(),
+                pvk.delta_g2_neg_pc.clone(),
+                G2Prepared::from(-vk.beta_g2),
+            ]
+            .into_iter()
+            .chain(vk.commitment_keys.iter().map(|key| G2Prepared::from(key.g)))
+            .chain([G2Prepared::from(g_sigma_neg)]),
+        );
+        Ok(Self {
+            vk,
+            pvk,
+            pairing_pvk,
+        })
+    }
+}
+
+// The hashes of the commitments, which extend the public inputs.
+//
+// return: `WrongLength` if the commitments don't match the commitment keys,
+//  and `InvalidCommitmentKey` if a committed index is out of the public inputs.
+pub fn commitment_hashes(
+    vk: &GnarkVerifyingKey,
+    commitments: &[G1Affine],
+    public_inputs: &[Fr],
+) -> Result<Vec<Fr>, GnarkError> {
+    if commitments.len() != vk.public_and_commitment_committed.len() {
+        return Err(GnarkError::WrongLength("commitments"));
+    }
+    commitments
+        .iter()
+        .zip(vk.public_and_commitment_committed.iter())
+        .map(|(commitment, committed)| {
+            let mut prehash = uncompressed_bytes(commitment);
+            for i in committed {
+                let input = (*i as usize)
+                    .checked_sub(1)
+                    .and_then(|i| public_inputs.get(i))
+                    .ok_or(GnarkError::InvalidCommitmentKey)?;
+                prehash.extend(input.into_bigint().to_bytes_be());
+            }
+            Ok(hash_to_field(&prehash, COMMITMENT_DST))
+        })
+        .collect()
+}
+
+// The challenge to batch the proofs of knowledge of the commitments.
+pub fn commitment_challenge(hashes: &[Fr]) -> Fr {
+    let bytes = hashes
+        .iter()
+        .flat_map(|h| h.into_bigint().to_bytes_be())
+        .collect::<Vec<_>>();
+    hash_to_field(&bytes, CHALLENGE_DST)
+}
+
+// X|Y, and (0, 0) for the infinity.
+fn uncompressed_bytes(point: &G1Affine) -> Vec<u8> {
+    let (x, y) = point
+        .xy()
+        .map_or((Fq::zero(), Fq::zero()), |(x, y)| (*x, *y));
+    [x.into_bigint().to_bytes_be(), y.into_bigint().to_bytes_be()].concat()
+}
+
+// hash_to_field of RFC 9380 with expand_message_xmd(sha256), which takes 48 bytes for one element of Fr.
+fn hash_to_field(msg: &[u8], dst: &[u8]) -> Fr {
+    Fr::from_be_bytes_mod_order(&expand_message_xmd(msg, dst, 48))
+}
+
+// expand_message_xmd of RFC 9380 with sha256.
+fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b0 = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut bi = Sha256::new()
+        .chain_update(b0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut uniform_bytes = bi.to_vec();
+    for i in 2..=len.div_ceil(32) {
+        let xor = b0
+            .iter()
+            .zip(bi.iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        bi = Sha256::new()
+            .chain_update(xor)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&bi);
+    }
+    uniform_bytes.truncate(len);
+    uniform_bytes
+}
+
+// Read the public witness written by `witness.MarshalBinary` of gnark.
+pub fn read_public_witness(bytes: &[u8]) -> Result<Vec<Fr>, GnarkError> {
+    let mut decoder = Decoder::new(bytes);
+    let nb_public = decoder.read_u32()? as usize;
+    let nb_secret = decoder.read_u32()? as usize;
+    let len = decoder.read_u32()? as usize;
+    if nb_secret != 0 || len != nb_public {
+        return Err(GnarkError::WrongLength("public witness"));
+    }
+    let inputs = (0..len)
+        .map(|_| field_from_be_bytes::<Fr>(decoder.take(FR_BYTES)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    decoder.finish()?;
+    Ok(inputs)
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], GnarkError> {
+        if self.bytes.len() < n {
+            return Err(GnarkError::UnexpectedEof);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn finish(&self) -> Result<(), GnarkError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(GnarkError::TrailingBytes)
+        }
+    }
+
+    fn peek_flag(&self) -> Result<u8, GnarkError> {
+        self.bytes
+            .first()
+            .map(|b| b & FLAG_MASK)
+            .ok_or(GnarkError::UnexpectedEof)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, GnarkError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, GnarkError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn read_u64_vec_vec(&mut self) -> Result<Vec<Vec<u64>>, GnarkError> {
+        (0..self.read_u32()?)
+            .map(|_| (0..self.read_u32()?).map(|_| self.read_u64()).collect())
+            .collect()
+    }
+
+    // Read the big-endian field element, which must be less than the modulus.
+    fn read_field<F: PrimeField>(&mut self, size: usize) -> Result<F, GnarkError> {
+        field_from_be_bytes(self.take(size)?)
+    }
+
+    // Read the first coordinate of a point, whose top two bits carry the flag and are cleared.
+    // The top bits of the other coordinates must be unset, as SetBytesCanonical of gnark requires.
+    fn read_flagged_field<F: PrimeField>(&mut self, size: usize) -> Result<F, GnarkError> {
+        let mut bytes = self.take(size)?.to_vec();
+        bytes[0] &= !FLAG_MASK;
+        field_from_be_bytes(&bytes)
+    }
+
+    // A1|A0, where A1 carries the flag if it's the first coordinate of the point.
+    fn read_fq2(&mut self, flagged: bool) -> Result<Fq2, GnarkError> {
+        let c1 = if flagged {
+            self.read_flagged_field::<Fq>(FQ_BYTES)?
+        } else {
+            self.read_field::<Fq>(FQ_BYTES)?
+        };
+        let c0 = self.read_field::<Fq>(FQ_BYTES)?;
+        Ok(Fq2::new(c0, c1))
+    }
+
+    fn read_g1(&mut self, name: &'static str) -> Result<G1Affine, GnarkError> {
+        let flag = self.peek_flag()?;
+        let x = self.read_flagged_field::<Fq>(FQ_BYTES)?;
+        let point = if flag == FLAG_UNCOMPRESSED {
+            let y = self.read_field::<Fq>(FQ_BYTES)?;
+            from_uncompressed(x, y)
+        } else {
+            from_compressed(x, flag, name)?
+        };
+        check_point(point, name)
+    }
+
+    fn read_g2(&mut self, name: &'static str) -> Result<G2Affine, GnarkError> {
+        let flag = self.peek_flag()?;
+        let x = self.read_fq2(true)?;
+        let point = if flag == FLAG_UNCOMPRESSED {
+            let y = self.read_fq2(false)?;
+            from_uncompressed(x, y)
+        } else {
+            from_compressed(x, flag, name)?
+        };
+        check_point(point, name)
+    }
+
+    fn read_g1_vec(&mut self, name: &'static str) -> Result<Vec<G1Affine>, GnarkError> {
+        (0..self.read_u32()?).map(|_| self.read_g1(name)).collect()
+    }
+}
+
+fn field_from_be_bytes<F: PrimeField>(bytes: &[u8]) -> Result<F, GnarkError> {
+    let n = BigUint::from_bytes_be(bytes);
+    if n >= F::MODULUS.into() {
+        return Err(GnarkError::InvalidField);
+    }
+    Ok(F::from(n))
+}
+
+// gnark takes (0, 0) as the infinity in affine.
+fn from_uncompressed<P: SWCurveConfig>(x: P::BaseField, y: P::BaseField) -> Affine<P> {
+    if x.is_zero() && y.is_zero() {
+        Affine::zero()
+    } else {
+        Affine::new_unchecked(x, y)
+    }
+}
+
+// Recover y from x, where the flag tells whether y is the lexicographically largest one.
+// It's the same order as ark's, which compares c1 first for Fq2.
+fn from_compressed<P: SWCurveConfig>(
+    x: P::BaseField,
+    flag: u8,
+    name: &'static str,
+) -> Result<Affine<P>, GnarkError> {
+    match flag {
+        FLAG_COMPRESSED_INFINITY if x.is_zero() => Ok(Affine::zero()),
+        FLAG_COMPRESSED_SMALLEST | FLAG_COMPRESSED_LARGEST => {
+            Affine::get_point_from_x_unchecked(x, flag == FLAG_COMPRESSED_LARGEST)
+                .ok_or(GnarkError::InvalidPoint(name))
+        }
+        _ => Err(GnarkError::InvalidPoint(name)),
+    }
+}
+
+// On curve and in the prime order subgroup.
+fn check_point<P: SWCurveConfig>(
+    point: Affine<P>,
+    name: &'static str,
+) -> Result<Affine<P>, GnarkError> {
+    if point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+        Ok(point)
+    } else {
+        Err(GnarkError::InvalidPoint(name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Groth16Verifier;
+    use ark_ff::One;
+    use ark_groth16::Groth16;
+    use std::str::FromStr;
+
+    const VK: &[u8] = include_bytes!("../fixtures/gnark/verifying_key.bin");
+    const VK_RAW: &[u8] = include_bytes!("../fixtures/gnark/verifying_key_raw.bin");
+    const PROOF: &[u8] = include_bytes!("../fixtures/gnark/proof.bin");
+    const PROOF_RAW: &[u8] = include_bytes!("../fixtures/gnark/proof_raw.bin");
+    const PUBLIC_WITNESS: &[u8] = include_bytes!("../fixtures/gnark/public_witness.bin");
+    const COMMITMENT_VK: &[u8] = include_bytes!("../fixtures/gnark/commitment/verifying_key.bin");
+    const COMMITMENT_PROOF: &[u8] = include_bytes!("../fixtures/gnark/commitment/proof.bin");
+    const COMMITMENT_PUBLIC_WITNESS: &[u8] =
+        include_bytes!("../fixtures/gnark/commitment/public_witness.bin");
+
+    #[test]
+    fn test_read_gnark_fixtures() {
+        let vk = GnarkVerifyingKey::read(VK).unwrap();
+        let proof = GnarkProof::read(PROOF).unwrap();
+        let public_inputs = read_public_witness(PUBLIC_WITNESS).unwrap();
+
+        // the compressed and uncompressed encodings are the same
+        assert_eq!(vk, GnarkVerifyingKey::read(VK_RAW).unwrap());
+        assert_eq!(proof, GnarkProof::read(PROOF_RAW).unwrap());
+
+        assert!(vk.commitment_keys.is_empty());
+        assert!(proof.commitments.is_empty());
+        assert!(proof.commitment_pok.is_zero());
+        assert_eq!(vk.num_public_inputs(), public_inputs.len());
+
+        let pvk = vk.to_prepared_verifying_key();
+        let groth16_proof = proof.to_proof();
+        assert!(Groth16::<Bn254>::verify_proof(&pvk, &groth16_proof, &public_inputs).unwrap());
+        assert!(Groth16Verifier::verify_proof(&pvk, &groth16_proof, &public_inputs, true).unwrap());
+    }
+
+    #[test]
+    fn test_read_gnark_commitment_fixtures() {
+        let vk = GnarkVerifyingKey::read(COMMITMENT_VK).unwrap();
+        let proof = GnarkProof::read(COMMITMENT_PROOF).unwrap();
+        let public_inputs = read_public_witness(COMMITMENT_PUBLIC_WITNESS).unwrap();
+
+        assert_eq!(vk.commitment_keys.len(), 1);
+        assert_eq!(vk.public_and_commitment_committed, vec![vec![1]]);
+        assert_eq!(vk.k.len(), 3);
+        assert_eq!(vk.num_public_inputs(), public_inputs.len());
+        assert_eq!(proof.commitments.len(), 1);
+        assert!(!proof.commitment_pok.is_zero());
+    }
+
+    #[test]
+    fn test_verify_proof_with_commitments() {
+        let rng = &mut ark_std::test_rng();
+        let vk = GnarkVerifyingKey::read(COMMITMENT_VK).unwrap();
+        let proof = GnarkProof::read(COMMITMENT_PROOF).unwrap();
+        let public_inputs = read_public_witness(COMMITMENT_PUBLIC_WITNESS).unwrap();
+
+        let hashes = commitment_hashes(&vk, &proof.commitments, &public_inputs).unwrap();
+        assert_eq!(
+            hashes,
+            vec![Fr::from_str(
+                "20138722033361462538300300349797901384389883511611454150170884886797629563591"
+            )
+            .unwrap()]
+        );
+
+        let cvk = CommitmentPreparedVerifyingKey::new(vk.clone()).unwrap();
+        assert!(
+            Groth16Verifier::verify_proof_with_commitments(&cvk, &proof, &public_inputs, rng)
+                .unwrap()
+        );
+
+        // wrong public input, which changes the hash as well
+        let mut wrong_inputs = public_inputs.clone();
+        wrong_inputs[0] += Fr::one();
+        assert!(
+            !Groth16Verifier::verify_proof_with_commitments(&cvk, &proof, &wrong_inputs, rng)
+                .unwrap()
+        );
+
+        // tampered proof of knowledge, while the Groth16 equation still holds
+        let mut tampered = proof.clone();
+        tampered.commitment_pok = (tampered.commitment_pok + G1Affine::generator()).into();
+        assert!(!Groth16Verifier::verify_proof_with_commitments(
+            &cvk,
+            &tampered,
+            &public_inputs,
+            rng
+        )
+        .unwrap());
+
+        // tampered commitment
+        let mut tampered = proof.clone();
+        tampered.commitments[0] = (tampered.commitments[0] + G1Affine::generator()).into();
+        assert!(!Groth16Verifier::verify_proof_with_commitments(
+            &cvk,
+            &tampered,
+            &public_inputs,
+            rng
+        )
+        .unwrap());
+
+        // missing commitment
+        let mut tampered = proof.clone();
+        tampered.commitments.clear();
+        assert!(Groth16Verifier::verify_proof_with_commitments(
+            &cvk,
+            &tampered,
+            &public_inputs,
+            rng
+        )
+        .is_err());
+
+        // the committed index out of the public inputs, which is 1-based
+        for index in [0, 2] {
+            let mut invalid = vk.clone();
+            invalid.public_and_commitment_committed = vec![vec![index]];
+            assert_eq!(
+                commitment_hashes(&invalid, &proof.commitments, &public_inputs),
+                Err(GnarkError::InvalidCommitmentKey)
+            );
+        }
+        assert_eq!(
+            commitment_hashes(&vk, &[], &public_inputs),
+            Err(GnarkError::WrongLength("commitments"))
+        );
+
+        // the commitment keys with different GSigmaNeg
+        let mut invalid = vk.clone();
+        invalid.commitment_keys.push(PedersenVerifyingKey {
+            g: vk.commitment_keys[0].g,
+            g_sigma_neg: vk.commitment_keys[0].g,
+        });
+        assert!(matches!(
+            CommitmentPreparedVerifyingKey::new(invalid),
+            Err(GnarkError::InvalidCommitmentKey)
+        ));
+    }
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // the test vectors of expand_message_xmd(sha256) from RFC 9380
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            hex(&expand_message_xmd(b"", dst, 0x20)),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            hex(&expand_message_xmd(b"abc", dst, 0x20)),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        assert_eq!(
+            GnarkProof::read(&PROOF[..PROOF.len() - 1]),
+            Err(GnarkError::UnexpectedEof)
+        );
+        assert_eq!(
+            GnarkProof::read(&[PROOF, &[0]].concat()),
+            Err(GnarkError::TrailingBytes)
+        );
+
+        // flip the flag of Ar between the smallest and largest Y, which gives -Ar
+        let mut flipped = PROOF.to_vec();
+        flipped[0] ^= FLAG_COMPRESSED_SMALLEST ^ FLAG_COMPRESSED_LARGEST;
+        let proof = GnarkProof::read(PROOF).unwrap();
+        assert_eq!(GnarkProof::read(&flipped).unwrap().ar, -proof.ar);
+
+        // the infinity flag with non-zero x
+        let mut invalid = PROOF.to_vec();
+        invalid[0] = (invalid[0] & !FLAG_MASK) | FLAG_COMPRESSED_INFINITY;
+        assert_eq!(
+            GnarkProof::read(&invalid),
+            Err(GnarkError::InvalidPoint("ar"))
+        );
+
+        // x is not less than the modulus
+        let mut invalid = PROOF.to_vec();
+        invalid[..FQ_BYTES].copy_from_slice(&[0xff; FQ_BYTES]);
+        assert_eq!(GnarkProof::read(&invalid), Err(GnarkError::InvalidField));
+
+        // the flag bits are only carried by the first coordinate, not by X.A0 of Bs
+        let mut invalid = PROOF.to_vec();
+        invalid[2 * FQ_BYTES] |= FLAG_COMPRESSED_SMALLEST;
+        assert_eq!(GnarkProof::read(&invalid), Err(GnarkError::InvalidField));
+
+        // the public witness with the secret part
+        let mut invalid = PUBLIC_WITNESS.to_vec();
+        invalid[7] = 1;
+        assert_eq!(
+            read_public_witness(&invalid),
+            Err(GnarkError::WrongLength("public witness"))
+        );
+    }
+}
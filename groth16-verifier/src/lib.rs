@@ -13,26 +13,46 @@
 //!  0. setup and generate Groth16's proof
 //!  1. prepare the inputs: msm with vk and public_inputs
 //!  2. check pairing with 4 pairings
-//!      2.1 setup for proving pairing: precompute lines and find_c.
-//!      2.2 generate pairing proof
-//!      2.2 verify the pairing proof.
+//!      2.1 setup for proving pairing: precompute lines for fixed Qi.
+//!      2.2 generate pairing proof: find_c, namely the witness (c, wi).
+//!      2.3 verify the pairing proof by redoing the merged miller loop with (c, wi).
 //!
+//! As `PreparedVerifyingKey` carries e(α,β), [`Groth16Verifier::verify_proof_with_target`] takes it as the target
+//! of the pairing equation, which leaves 3 pairings: `e(IC,-γ)*e(C,-δ)*e(A,B) = e(α,β)`.
 //!
-//! Note: Only support Bn254 for now.
+//! To verify many proofs of one circuit, build a [`RecursivePreparedVerifyingKey`] once, which caches the lines
+//! of the fixed Qi and e(α,β), so that only the lines of the proof's B are computed per proof.
+//! [`Groth16Verifier::batch_verify`] further combines N proofs with random scalars into one pairing proof,
+//! and [`Groth16Verifier::batch_verify_heterogeneous`] does it over different vks, sharing the identical G2 points.
+//! [`snarkpack`] aggregates the proofs into a log-size SnarkPack proof, whose final pairing checks are proved the same way.
+//!
+//! The verification key, proof and public inputs of snarkjs/circom can be imported by [`snarkjs`], and those of gnark by [`gnark`].
+//! gnark's variant with the pedersen commitments is verified by [`Groth16Verifier::verify_proof_with_commitments`],
+//! which proves its Groth16 and proof-of-knowledge equations in one multi-pairing.
+//!
+//! Note: Support Bn254, and BLS12 curves(e.g. BLS12-377) in [`bls12`].
+pub mod bls12;
 pub mod dummy_circuit;
+pub mod gnark;
+pub mod snarkjs;
+pub mod snarkpack;
 #[cfg(test)]
 mod test;
 
-use ark_bn254::{Bn254, Fr, G1Affine, G1Projective};
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine};
 use ark_ec::bn::G2Prepared;
-use ark_ec::pairing::Pairing;
+use ark_ec::pairing::PairingOutput;
 use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
-use ark_groth16::{Groth16, PreparedVerifyingKey, Proof};
-use ark_relations::r1cs::Result as R1CSResult;
+use ark_ff::One;
+use ark_groth16::{prepare_verifying_key, Groth16, PreparedVerifyingKey, Proof, VerifyingKey};
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_std::rand::Rng;
+use ark_std::UniformRand;
+use gnark::{CommitmentPreparedVerifyingKey, GnarkProof};
+use on_proving_pairings::error::PairingError;
 use on_proving_pairings::prover::PairingProver;
-use on_proving_pairings::setup::PairingPVKey;
+use on_proving_pairings::setup::{PairingPVKey, PairingTarget};
 use on_proving_pairings::verifier::PairingVerifier;
-use std::ops::Neg;
 
 pub struct Groth16Verifier;
 
@@ -60,7 +80,9 @@ impl Groth16Verifier {
         pvk: &PreparedVerifyingKey<Bn254>,
         public_inputs: &[Fr],
     ) -> R1CSResult<G1Projective> {
-        assert_eq!(public_inputs.len() + 1, pvk.vk.gamma_abc_g1.len());
+        if (public_inputs.len() + 1) != pvk.vk.gamma_abc_g1.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
         let g_ic = pvk.vk.gamma_abc_g1[0].into_group();
 
         let g_ic = g_ic + G1Projective::msm(&pvk.vk.gamma_abc_g1[1..], &public_inputs).unwrap();
@@ -70,48 +92,449 @@ impl Groth16Verifier {
 
     // Verifier by applying with new paper:
     //
+    // It precomputes the lines of the fixed Qi on each call, see `verify_proof_with_prepared_vk`
+    // to reuse them over the proofs of the same circuit.
+    //
     // Porting from `ark_groth16::Groth16::verify_proof_with_prepared_inputs`
     pub fn verify_proof_with_recursive_verifier(
         pvk: &PreparedVerifyingKey<Bn254>,
         proof: &Proof<Bn254>,
         prepared_inputs: &G1Projective,
     ) -> R1CSResult<bool> {
-        let beta_prepared: G2Prepared<ark_bn254::Config> = (pvk.vk.beta_g2.clone().neg()).into();
-        let sum_ai_abc_gamma = prepared_inputs.into_affine();
-
-        // Pi
-        let a = vec![
-            sum_ai_abc_gamma.into(),
-            proof.c.into(),
-            pvk.vk.alpha_g1.into(),
-            <G1Affine as Into<<Bn254 as Pairing>::G1Prepared>>::into(proof.a),
-        ];
-        // Qi - proof.a(Q4), is non-fixed.
-        let b = vec![
+        // setup: precompute lines for fixed [Q1,Q2,Q3]. proof.b(Q4) is non-fixed.
+        let pairing_pvk = PairingPVKey::setup(vec![
             pvk.gamma_g2_neg_pc.clone(),
             pvk.delta_g2_neg_pc.clone(),
             (-pvk.vk.beta_g2).into(),
-            proof.b.into(),
-        ];
-
-        // Prove and verify pairing.
-        // finding_c
-        let pairing_pvk = PairingPVKey::setup(a.clone(), b.clone());
+        ]);
 
         // eval_points: [P1,P2,P3]
-        let eval_points = vec![sum_ai_abc_gamma, proof.c, pvk.vk.alpha_g1];
-        // precompute lines: [Q1,Q2,Q3]
-        let q_prepared_lines = b[0..3].to_vec();
+        let eval_points = vec![prepared_inputs.into_affine(), proof.c, pvk.vk.alpha_g1];
+        Self::prove_and_verify_pairing(
+            &pairing_pvk,
+            eval_points,
+            vec![proof.a],
+            vec![proof.b],
+            None,
+        )
+    }
+
+    // Prepare the target e(α,β) of `verify_proof_with_target`, which is `pvk.alpha_g1_beta_g2`.
+    //
+    // It's statement-independent, so it can be reused by all the proofs over the same vk.
+    pub fn prepare_target(
+        pvk: &PreparedVerifyingKey<Bn254>,
+    ) -> R1CSResult<PairingTarget<ark_bn254::Config>> {
+        PairingTarget::new(PairingOutput(pvk.alpha_g1_beta_g2))
+            .map_err(|_| SynthesisError::MalformedVerifyingKey)
+    }
+
+    // Verifier with 3 pairings, taking the precomputed e(α,β) as the target:
+    //  e(IC,-γ) * e(C,-δ) * e(A,B) = e(α,β)
+    //
+    // Compared with `verify_proof_with_recursive_verifier`, it saves the miller loop of e(α,-β),
+    // for both the prover and the verifier.
+    //
+    // params:
+    //  @target: e(α,β), prepared by `prepare_target`
+    pub fn verify_proof_with_target(
+        pvk: &PreparedVerifyingKey<Bn254>,
+        target: &PairingTarget<ark_bn254::Config>,
+        proof: &Proof<Bn254>,
+        prepared_inputs: &G1Projective,
+    ) -> R1CSResult<bool> {
+        // setup: precompute lines for fixed [Q1,Q2]. proof.b(Q3) is non-fixed.
+        let pairing_pvk = PairingPVKey::setup(vec![
+            pvk.gamma_g2_neg_pc.clone(),
+            pvk.delta_g2_neg_pc.clone(),
+        ]);
 
-        let final_f = PairingProver::prove_quad_pairing(
+        // eval_points: [P1,P2]
+        let eval_points = vec![prepared_inputs.into_affine(), proof.c];
+        Self::prove_and_verify_pairing(
+            &pairing_pvk,
             eval_points,
-            &q_prepared_lines,
-            proof.a,
-            proof.b,
+            vec![proof.a],
+            vec![proof.b],
+            Some(target),
+        )
+    }
+
+    // Same as `verify_proof_with_recursive_verifier`, with the cached lines of the fixed [-γ,-δ,-β].
+    pub fn verify_proof_with_prepared_vk(
+        rvk: &RecursivePreparedVerifyingKey,
+        proof: &Proof<Bn254>,
+        prepared_inputs: &G1Projective,
+    ) -> R1CSResult<bool> {
+        let eval_points = vec![prepared_inputs.into_affine(), proof.c, rvk.pvk.vk.alpha_g1];
+        Self::prove_and_verify_pairing(
+            &rvk.pairing_pvk,
+            eval_points,
+            vec![proof.a],
+            vec![proof.b],
+            None,
+        )
+    }
+
+    // Same as `verify_proof_with_target`, with the cached lines of the fixed [-γ,-δ] and the target e(α,β).
+    pub fn verify_proof_with_prepared_vk_and_target(
+        rvk: &RecursivePreparedVerifyingKey,
+        proof: &Proof<Bn254>,
+        prepared_inputs: &G1Projective,
+    ) -> R1CSResult<bool> {
+        let eval_points = vec![prepared_inputs.into_affine(), proof.c];
+        Self::prove_and_verify_pairing(
+            &rvk.target_pvk,
+            eval_points,
+            vec![proof.a],
+            vec![proof.b],
+            Some(&rvk.target),
+        )
+    }
+
+    // Batch verify the proofs over the same vk with one pairing proof.
+    //
+    // With random scalars ri, the N equations are combined into one:
+    //  e(Σri*ICi,-γ) * e(Σri*Ci,-δ) * e((Σri)*α,-β) * Π e(ri*Ai,Bi) = 1
+    // where the fixed pairs merge into G1 msms, and one non-fixed pair remains per proof's B.
+    // Then it proves and verifies a single pairing proof (c, wi) for the whole batch.
+    //
+    // params:
+    //  @public_inputs: the public inputs of each proof, in the same order with proofs.
+    //
+    // return: `MalformedVerifyingKey` if the numbers of proofs and public inputs differ,
+    //  same as a wrong length of public inputs in `prepare_inputs`.
+    pub fn batch_verify<R: Rng>(
+        rvk: &RecursivePreparedVerifyingKey,
+        proofs: &[Proof<Bn254>],
+        public_inputs: &[Vec<Fr>],
+        rng: &mut R,
+    ) -> R1CSResult<bool> {
+        if proofs.len() != public_inputs.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+        if proofs.is_empty() {
+            return Ok(true);
+        }
+
+        // the first scalar can be 1 without loss of soundness.
+        let scalars = std::iter::once(Fr::one())
+            .chain((1..proofs.len()).map(|_| Fr::rand(rng)))
+            .collect::<Vec<_>>();
+
+        let prepared_inputs = public_inputs
+            .iter()
+            .map(|pi| Self::prepare_inputs(&rvk.pvk, pi))
+            .collect::<R1CSResult<Vec<_>>>()?;
+        let prepared_inputs = G1Projective::normalize_batch(&prepared_inputs);
+        let proof_c = proofs.iter().map(|proof| proof.c).collect::<Vec<_>>();
+
+        // eval_points: [Σri*ICi, Σri*Ci, (Σri)*α]
+        let sum_ic = G1Projective::msm(&prepared_inputs, &scalars).unwrap();
+        let sum_c = G1Projective::msm(&proof_c, &scalars).unwrap();
+        let sum_alpha = rvk.pvk.vk.alpha_g1 * scalars.iter().sum::<Fr>();
+        let eval_points = G1Projective::normalize_batch(&[sum_ic, sum_c, sum_alpha]);
+
+        // non-fixed: [ri*Ai] with [Bi]
+        let non_fixed_p = G1Projective::normalize_batch(
+            &proofs
+                .iter()
+                .zip(scalars.iter())
+                .map(|(proof, r)| proof.a * r)
+                .collect::<Vec<_>>(),
+        );
+        let non_fixed_q = proofs.iter().map(|proof| proof.b).collect::<Vec<_>>();
+
+        Self::prove_and_verify_pairing(
+            &rvk.pairing_pvk,
+            eval_points,
+            non_fixed_p,
+            non_fixed_q,
+            None,
+        )
+    }
+
+    // Batch verify the proofs over different vks with one pairing proof.
+    //
+    // With random scalars ri, the fixed pairs of all proofs are grouped by their G2 point, e.g. the
+    // identical δ across circuits after setup reuse, and each group merges into one G1 msm:
+    //  Π_Q e(Σ ri*Pi, Q) * Π e(ri*Ai,Bi) = 1, where Q ∈ {-γk,-δk,-βk} and Pi ∈ {ICi,Ci,α}
+    //
+    // params:
+    //  @batch: the (rvk, proof, public_inputs) triples, where the rvks can be different.
+    //
+    // return: the indices of the failing proofs, which is empty if the batch is accepted.
+    //  A proof with the wrong number of public inputs is reported as failing, without aborting the batch.
+    //  It verifies each proof only when the batch is rejected, to report which one failed.
+    pub fn batch_verify_heterogeneous<R: Rng>(
+        batch: &[(&RecursivePreparedVerifyingKey, &Proof<Bn254>, &[Fr])],
+        rng: &mut R,
+    ) -> R1CSResult<Vec<usize>> {
+        // a proof with the wrong number of public inputs fails on its own, and the rest are batched.
+        let mut failed = vec![];
+        let mut indices = vec![];
+        let mut valid = vec![];
+        let mut prepared_inputs = vec![];
+        for (i, entry) in batch.iter().enumerate() {
+            match Self::prepare_inputs(&entry.0.pvk, entry.2) {
+                Ok(prepared) => {
+                    indices.push(i);
+                    valid.push(*entry);
+                    prepared_inputs.push(prepared);
+                }
+                Err(SynthesisError::MalformedVerifyingKey) => failed.push(i),
+                Err(e) => return Err(e),
+            }
+        }
+        if valid.is_empty() {
+            return Ok(failed);
+        }
+
+        // the first scalar can be 1 without loss of soundness.
+        let scalars = std::iter::once(Fr::one())
+            .chain((1..valid.len()).map(|_| Fr::rand(rng)))
+            .collect::<Vec<_>>();
+
+        let (pairing_pvk, eval_points) =
+            Self::merge_fixed_pairs(&valid, &prepared_inputs, &scalars);
+
+        // non-fixed: [ri*Ai] with [Bi]
+        let non_fixed_p = G1Projective::normalize_batch(
+            &valid
+                .iter()
+                .zip(scalars.iter())
+                .map(|((_, proof, _), r)| proof.a * r)
+                .collect::<Vec<_>>(),
+        );
+        let non_fixed_q = valid
+            .iter()
+            .map(|(_, proof, _)| proof.b)
+            .collect::<Vec<_>>();
+
+        if Self::prove_and_verify_pairing(
             &pairing_pvk,
+            eval_points,
+            non_fixed_p,
+            non_fixed_q,
+            None,
+        )? {
+            return Ok(failed);
+        }
+
+        // the batch is rejected: find the failing proofs one by one.
+        for (i, ((rvk, proof, _), prepared_inputs)) in
+            indices.iter().zip(valid.iter().zip(prepared_inputs.iter()))
+        {
+            if !Self::verify_proof_with_prepared_vk_and_target(rvk, proof, prepared_inputs)? {
+                failed.push(*i);
+            }
+        }
+        failed.sort_unstable();
+        Ok(failed)
+    }
+
+    // Group the fixed pairs of the batch by their G2 point, and merge the G1 points of each group with the scalars.
+    //
+    // return: the pairing pvk with the cached lines of the distinct G2 points, and the merged eval points in order.
+    fn merge_fixed_pairs(
+        batch: &[(&RecursivePreparedVerifyingKey, &Proof<Bn254>, &[Fr])],
+        prepared_inputs: &[G1Projective],
+        scalars: &[Fr],
+    ) -> (PairingPVKey<ark_bn254::Config>, Vec<G1Affine>) {
+        // (Q, lines of Q, bases, scalars)
+        type Group<'a> = (
+            G2Affine,
+            &'a G2Prepared<ark_bn254::Config>,
+            Vec<G1Affine>,
+            Vec<Fr>,
+        );
+        let mut groups: Vec<Group> = vec![];
+
+        for (((rvk, proof, _), prepared_inputs), r) in
+            batch.iter().zip(prepared_inputs.iter()).zip(scalars.iter())
+        {
+            let vk = &rvk.pvk.vk;
+            // the lines of rvk.pairing_pvk are in the order of [-γ,-δ,-β].
+            let fixed_q = [-vk.gamma_g2, -vk.delta_g2, -vk.beta_g2];
+            let eval_points = [prepared_inputs.into_affine(), proof.c, vk.alpha_g1];
+
+            for ((q, lines), p) in fixed_q
+                .into_iter()
+                .zip(rvk.pairing_pvk.lines.iter())
+                .zip(eval_points)
+            {
+                match groups.iter_mut().find(|group| group.0 == q) {
+                    Some(group) => {
+                        group.2.push(p);
+                        group.3.push(*r);
+                    }
+                    None => groups.push((q, lines, vec![p], vec![*r])),
+                }
+            }
+        }
+
+        let pairing_pvk = PairingPVKey::setup(groups.iter().map(|group| group.1.clone()));
+        let eval_points = G1Projective::normalize_batch(
+            &groups
+                .iter()
+                .map(|group| G1Projective::msm(&group.2, &group.3).unwrap())
+                .collect::<Vec<_>>(),
+        );
+        (pairing_pvk, eval_points)
+    }
+
+    // Verify the gnark's Groth16 proof with the pedersen commitments [Dj] and their proof of knowledge.
+    //
+    // The hash hj of each Dj and its committed public inputs extends the public inputs, and the two equations:
+    //  Groth16: e(kSum,-γ) * e(Krs,-δ) * e(α,-β) * e(Ar,Bs) = 1, where kSum = K0 + Σai*Ki + Σhj*K_{n+j} + ΣDj
+    //  PoK: Π e(r^j*Dj,Gj) * e(PoK,GSigmaNeg) = 1, where r is the challenge hashed from [hj]
+    // are combined with a random ρ into one pairing proof:
+    //  e(kSum,-γ) * e(Krs,-δ) * e(α,-β) * Π e(ρ*r^j*Dj,Gj) * e(ρ*PoK,GSigmaNeg) * e(Ar,Bs) = 1
+    // where all the G2 points but Bs are fixed, whose lines are cached in cvk.
+    //
+    // params:
+    //  @public_inputs: the public inputs, excluding the hashes of the commitments.
+    pub fn verify_proof_with_commitments<R: Rng>(
+        cvk: &CommitmentPreparedVerifyingKey,
+        proof: &GnarkProof,
+        public_inputs: &[Fr],
+        rng: &mut R,
+    ) -> R1CSResult<bool> {
+        if proof.commitments.len() != cvk.vk.commitment_keys.len()
+            || public_inputs.len() != cvk.vk.num_public_inputs()
+        {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+
+        // kSum: prepare the inputs extended with [hj], then add [Dj].
+        let hashes = gnark::commitment_hashes(&cvk.vk, &proof.commitments, public_inputs)
+            .map_err(|_| SynthesisError::MalformedVerifyingKey)?;
+        let public_inputs = [public_inputs, &hashes].concat();
+        let k_sum = proof
+            .commitments
+            .iter()
+            .fold(Self::prepare_inputs(&cvk.pvk, &public_inputs)?, |acc, d| {
+                acc + d
+            });
+
+        // [ρ*r^j*Dj] with r^0 = 1, and ρ*PoK
+        let challenge = gnark::commitment_challenge(&hashes);
+        let rho = Fr::rand(rng);
+        let mut scalar = rho;
+        let mut points = vec![k_sum, proof.krs.into(), cvk.vk.alpha_g1.into()];
+        for d in proof.commitments.iter() {
+            points.push(*d * scalar);
+            scalar *= challenge;
+        }
+        points.push(proof.commitment_pok * rho);
+        let eval_points = G1Projective::normalize_batch(&points);
+
+        Self::prove_and_verify_pairing(
+            &cvk.pairing_pvk,
+            eval_points,
+            vec![proof.ar],
+            vec![proof.bs],
+            None,
+        )
+    }
+
+    // Prove and verify the pairing equation, where the eval points map with the fixed lines of pairing_pvk,
+    // and the non-fixed Pi map with the non-fixed Qi.
+    //  e(P1,Q1)*...*e(Pn,Qn) = T, where T = 1 if target is None.
+    fn prove_and_verify_pairing(
+        pairing_pvk: &PairingPVKey<ark_bn254::Config>,
+        eval_points: Vec<G1Affine>,
+        non_fixed_p: Vec<G1Affine>,
+        non_fixed_q: Vec<G2Affine>,
+        target: Option<&PairingTarget<ark_bn254::Config>>,
+    ) -> R1CSResult<bool> {
+        // prove: finding_c, of f * f_T^{-1} if the target is given.
+        let pairing_proof = match target {
+            Some(target) => PairingProver::prove_with_target(
+                pairing_pvk,
+                eval_points.clone(),
+                non_fixed_p.clone(),
+                non_fixed_q.clone(),
+                target,
+            ),
+            None => PairingProver::prove(
+                pairing_pvk,
+                eval_points.clone(),
+                non_fixed_p.clone(),
+                non_fixed_q.clone(),
+            ),
+        };
+        let pairing_proof = match pairing_proof {
+            Ok(pairing_proof) => pairing_proof,
+            // the pairing product is not T, namely the groth16 proof is invalid.
+            Err(PairingError::NotRthResidue) => return Ok(false),
+            Err(_) => return Err(SynthesisError::Unsatisfiable),
+        };
+
+        // verify with the pairing proof (c, wi), without the miller loop output f.
+        match target {
+            Some(target) => PairingVerifier::verify_with_target(
+                pairing_pvk,
+                eval_points,
+                non_fixed_p,
+                non_fixed_q,
+                target,
+                &pairing_proof,
+            ),
+            None => PairingVerifier::verify(
+                pairing_pvk,
+                eval_points,
+                non_fixed_p,
+                non_fixed_q,
+                &pairing_proof,
+            ),
+        }
+        .map_err(|_| SynthesisError::Unsatisfiable)
+    }
+}
+
+// The verifying key of the recursive verifier, which caches the miller lines of the fixed Qi.
+//
+// It's built once from the vk, then verifying the proofs of the same circuit does no G2 work
+// beyond the non-fixed proof.b.
+pub struct RecursivePreparedVerifyingKey {
+    pub pvk: PreparedVerifyingKey<Bn254>,
+    // lines for the fixed [-γ,-δ,-β], which map with the eval points [IC,C,α] in order.
+    pub pairing_pvk: PairingPVKey<ark_bn254::Config>,
+    // lines for the fixed [-γ,-δ] of the 3-pairing mode, which map with the eval points [IC,C] in order.
+    pub target_pvk: PairingPVKey<ark_bn254::Config>,
+    // the target e(α,β) of the 3-pairing mode
+    pub target: PairingTarget<ark_bn254::Config>,
+}
+
+impl RecursivePreparedVerifyingKey {
+    pub fn new(vk: &VerifyingKey<Bn254>) -> R1CSResult<Self> {
+        prepare_verifying_key(vk).try_into()
+    }
+}
+
+impl TryFrom<PreparedVerifyingKey<Bn254>> for RecursivePreparedVerifyingKey {
+    type Error = SynthesisError;
+
+    fn try_from(pvk: PreparedVerifyingKey<Bn254>) -> R1CSResult<Self> {
+        let target_pvk = PairingPVKey::setup(vec![
+            pvk.gamma_g2_neg_pc.clone(),
+            pvk.delta_g2_neg_pc.clone(),
+        ]);
+        let pairing_pvk = PairingPVKey::setup(
+            target_pvk
+                .lines
+                .iter()
+                .cloned()
+                .chain([G2Prepared::from(-pvk.vk.beta_g2)]),
         );
+        let target = Groth16Verifier::prepare_target(&pvk)?;
 
-        // verify
-        Ok(PairingVerifier::verify(&pairing_pvk, final_f))
+        Ok(Self {
+            pvk,
+            pairing_pvk,
+            target_pvk,
+            target,
+        })
     }
 }
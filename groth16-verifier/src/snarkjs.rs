@@ -0,0 +1,300 @@
+// Import the Groth16 verification key, proof and public inputs of snarkjs/circom over bn128(BN254).
+//
+// snarkjs writes them in JSON with the decimal strings:
+//  G1: [x, y, z], the jacobian coordinates, with z = 1 normally and [0, 1, 0] for the infinity.
+//  G2: [[x.c0, x.c1], [y.c0, y.c1], [z.c0, z.c1]], the real part first.
+//      Note that the solidity verifier of snarkjs swaps them into [c1, c0], but the JSON doesn't.
+//  public.json: [input1, input2, ...]
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::AffineRepr;
+use ark_ff::{Field, PrimeField};
+use ark_groth16::{prepare_verifying_key, PreparedVerifyingKey, Proof, VerifyingKey};
+use num_bigint::BigUint;
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+// Errors of parsing the snarkjs JSON.
+#[derive(Debug)]
+pub enum SnarkjsError {
+    Json(serde_json::Error),
+    // The protocol or curve isn't groth16 over bn128.
+    Unsupported(String),
+    // The string isn't a decimal integer less than the modulus.
+    InvalidField(String),
+    // The point isn't on the curve or in the subgroup.
+    InvalidPoint(&'static str),
+    // The number of coordinates, IC or public inputs mismatch.
+    WrongLength(&'static str),
+}
+
+impl fmt::Display for SnarkjsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnarkjsError::Json(err) => write!(f, "invalid json: {}", err),
+            SnarkjsError::Unsupported(name) => write!(f, "unsupported {}", name),
+            SnarkjsError::InvalidField(s) => write!(f, "invalid field element: {}", s),
+            SnarkjsError::InvalidPoint(name) => write!(f, "{} is not a valid point", name),
+            SnarkjsError::WrongLength(name) => write!(f, "the length of {} mismatches", name),
+        }
+    }
+}
+
+impl std::error::Error for SnarkjsError {}
+
+impl From<serde_json::Error> for SnarkjsError {
+    fn from(err: serde_json::Error) -> Self {
+        SnarkjsError::Json(err)
+    }
+}
+
+// verification_key.json
+#[derive(Clone, Debug, Deserialize)]
+pub struct SnarkjsVerifyingKey {
+    pub protocol: String,
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    pub vk_alpha_1: Vec<String>,
+    pub vk_beta_2: Vec<Vec<String>>,
+    pub vk_gamma_2: Vec<Vec<String>>,
+    pub vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    pub ic: Vec<Vec<String>>,
+}
+
+// proof.json
+#[derive(Clone, Debug, Deserialize)]
+pub struct SnarkjsProof {
+    pub protocol: String,
+    pub curve: String,
+    pub pi_a: Vec<String>,
+    pub pi_b: Vec<Vec<String>>,
+    pub pi_c: Vec<String>,
+}
+
+impl SnarkjsVerifyingKey {
+    pub fn from_json(json: &str) -> Result<Self, SnarkjsError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_verifying_key(&self) -> Result<VerifyingKey<Bn254>, SnarkjsError> {
+        check_protocol(&self.protocol, &self.curve)?;
+        if self.ic.len() != self.n_public + 1 {
+            return Err(SnarkjsError::WrongLength("IC"));
+        }
+        Ok(VerifyingKey {
+            alpha_g1: parse_g1(&self.vk_alpha_1, "vk_alpha_1")?,
+            beta_g2: parse_g2(&self.vk_beta_2, "vk_beta_2")?,
+            gamma_g2: parse_g2(&self.vk_gamma_2, "vk_gamma_2")?,
+            delta_g2: parse_g2(&self.vk_delta_2, "vk_delta_2")?,
+            gamma_abc_g1: self
+                .ic
+                .iter()
+                .map(|ic| parse_g1(ic, "IC"))
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+
+    pub fn to_prepared_verifying_key(&self) -> Result<PreparedVerifyingKey<Bn254>, SnarkjsError> {
+        Ok(prepare_verifying_key(&self.to_verifying_key()?))
+    }
+}
+
+impl SnarkjsProof {
+    pub fn from_json(json: &str) -> Result<Self, SnarkjsError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_proof(&self) -> Result<Proof<Bn254>, SnarkjsError> {
+        check_protocol(&self.protocol, &self.curve)?;
+        Ok(Proof {
+            a: parse_g1(&self.pi_a, "pi_a")?,
+            b: parse_g2(&self.pi_b, "pi_b")?,
+            c: parse_g1(&self.pi_c, "pi_c")?,
+        })
+    }
+}
+
+// Parse the public.json.
+pub fn parse_public_inputs(json: &str) -> Result<Vec<Fr>, SnarkjsError> {
+    let inputs: Vec<String> = serde_json::from_str(json)?;
+    inputs
+        .iter()
+        .map(|input| parse_field::<Fr>(input))
+        .collect()
+}
+
+fn check_protocol(protocol: &str, curve: &str) -> Result<(), SnarkjsError> {
+    if protocol != "groth16" {
+        return Err(SnarkjsError::Unsupported(format!("protocol {}", protocol)));
+    }
+    if curve != "bn128" && curve != "bn254" {
+        return Err(SnarkjsError::Unsupported(format!("curve {}", curve)));
+    }
+    Ok(())
+}
+
+// Parse the decimal string, which should be less than the modulus.
+fn parse_field<F: PrimeField>(s: &str) -> Result<F, SnarkjsError> {
+    let n = BigUint::from_str(s).map_err(|_| SnarkjsError::InvalidField(s.to_string()))?;
+    if n >= F::MODULUS.into() {
+        return Err(SnarkjsError::InvalidField(s.to_string()));
+    }
+    Ok(F::from(n))
+}
+
+// [c0, c1]
+fn parse_fq2(c: &[String]) -> Result<Fq2, SnarkjsError> {
+    if c.len() != 2 {
+        return Err(SnarkjsError::WrongLength("Fq2"));
+    }
+    Ok(Fq2::new(parse_field(&c[0])?, parse_field(&c[1])?))
+}
+
+// Convert the jacobian (x, y, z) into affine: (x/z^2, y/z^3).
+fn jacobian_to_affine<F: Field>(x: F, y: F, z: F) -> Option<(F, F)> {
+    if z.is_zero() {
+        return None;
+    }
+    let z_inv = z.inverse().unwrap();
+    let z_inv2 = z_inv.square();
+    Some((x * z_inv2, y * z_inv2 * z_inv))
+}
+
+fn parse_g1(p: &[String], name: &'static str) -> Result<G1Affine, SnarkjsError> {
+    if p.len() != 3 {
+        return Err(SnarkjsError::WrongLength(name));
+    }
+    let (x, y, z): (Fq, Fq, Fq) = (
+        parse_field(&p[0])?,
+        parse_field(&p[1])?,
+        parse_field(&p[2])?,
+    );
+    let point = match jacobian_to_affine(x, y, z) {
+        Some((x, y)) => G1Affine::new_unchecked(x, y),
+        None => G1Affine::zero(),
+    };
+    check_point(point, name)
+}
+
+fn parse_g2(p: &[Vec<String>], name: &'static str) -> Result<G2Affine, SnarkjsError> {
+    if p.len() != 3 {
+        return Err(SnarkjsError::WrongLength(name));
+    }
+    let (x, y, z) = (parse_fq2(&p[0])?, parse_fq2(&p[1])?, parse_fq2(&p[2])?);
+    let point = match jacobian_to_affine(x, y, z) {
+        Some((x, y)) => G2Affine::new_unchecked(x, y),
+        None => G2Affine::zero(),
+    };
+    check_point(point, name)
+}
+
+// On curve and in the prime order subgroup.
+fn check_point<P: SWCurveConfig>(
+    point: Affine<P>,
+    name: &'static str,
+) -> Result<Affine<P>, SnarkjsError> {
+    if point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+        Ok(point)
+    } else {
+        Err(SnarkjsError::InvalidPoint(name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Groth16Verifier;
+    use ark_groth16::Groth16;
+
+    const VK_JSON: &str = include_str!("../fixtures/snarkjs/verification_key.json");
+    const PROOF_JSON: &str = include_str!("../fixtures/snarkjs/proof.json");
+    const PUBLIC_JSON: &str = include_str!("../fixtures/snarkjs/public.json");
+
+    #[test]
+    fn test_verify_snarkjs_fixtures() {
+        let pvk = SnarkjsVerifyingKey::from_json(VK_JSON)
+            .unwrap()
+            .to_prepared_verifying_key()
+            .unwrap();
+        let proof = SnarkjsProof::from_json(PROOF_JSON)
+            .unwrap()
+            .to_proof()
+            .unwrap();
+        let public_inputs = parse_public_inputs(PUBLIC_JSON).unwrap();
+        // the single public input of the fixture
+        assert_eq!(public_inputs, vec![Fr::from(33u64)]);
+
+        assert!(Groth16::<Bn254>::verify_proof(&pvk, &proof, &public_inputs).unwrap());
+        assert!(Groth16Verifier::verify_proof(&pvk, &proof, &public_inputs, true).unwrap());
+
+        let wrong_inputs = vec![public_inputs[0] + public_inputs[0]];
+        assert!(!Groth16Verifier::verify_proof(&pvk, &proof, &wrong_inputs, true).unwrap());
+    }
+
+    #[test]
+    fn test_parse_points() {
+        let proof = SnarkjsProof::from_json(PROOF_JSON).unwrap();
+        let a = parse_g1(&proof.pi_a, "pi_a").unwrap();
+
+        // jacobian with z = 2
+        let two = Fq::from(2u64);
+        let jacobian = [a.x * two.square(), a.y * two.square() * two, two]
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(parse_g1(&jacobian, "pi_a").unwrap(), a);
+
+        // infinity
+        let infinity = ["0", "1", "0"].map(String::from);
+        assert!(parse_g1(&infinity, "pi_a").unwrap().is_zero());
+
+        // not on curve
+        let mut invalid = proof.pi_a.clone();
+        invalid[1] = "1".to_string();
+        assert!(matches!(
+            parse_g1(&invalid, "pi_a"),
+            Err(SnarkjsError::InvalidPoint("pi_a"))
+        ));
+
+        // not less than the modulus
+        let mut invalid = proof.pi_a.clone();
+        invalid[0] = BigUint::from(Fq::MODULUS).to_string();
+        assert!(matches!(
+            parse_g1(&invalid, "pi_a"),
+            Err(SnarkjsError::InvalidField(_))
+        ));
+
+        // G2 with the swapped [c1, c0]
+        let swapped = proof
+            .pi_b
+            .iter()
+            .map(|c| vec![c[1].clone(), c[0].clone()])
+            .collect::<Vec<_>>();
+        assert!(parse_g2(&swapped, "pi_b").is_err());
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let mut vk = SnarkjsVerifyingKey::from_json(VK_JSON).unwrap();
+        vk.n_public += 1;
+        assert!(matches!(
+            vk.to_verifying_key(),
+            Err(SnarkjsError::WrongLength("IC"))
+        ));
+
+        let mut proof = SnarkjsProof::from_json(PROOF_JSON).unwrap();
+        proof.protocol = "plonk".to_string();
+        assert!(matches!(
+            proof.to_proof(),
+            Err(SnarkjsError::Unsupported(_))
+        ));
+
+        assert!(matches!(
+            parse_public_inputs("[\"0x1\"]"),
+            Err(SnarkjsError::InvalidField(_))
+        ));
+    }
+}
@@ -0,0 +1,702 @@
+// SnarkPack: aggregate Groth16 proofs over the same vk, and discharge the final pairing checks
+// with the pairing proof (c, wi) instead of the `final_exponentiation`.
+//
+// Following SnarkPack(https://eprint.iacr.org/2021/529), the aggregate proof shows:
+//  1. com_ab and com_c commit to [Ai],[Bi] and [Ci] with the pair commitment of the keys v and w.
+//  2. TIPP: Z_AB = Π e(Ai,Bi)^{r^i}, and MIPP: Z_C = Π Ci^{r^i}, by the GIPA recursion
+//     and the KZG openings of the final keys. [Ai],[Ci] are rescaled by r^i and v by r^{-i},
+//     so MIPP is over the rescaled [Ci] and [1,...,1].
+//  3. Groth16: Z_AB = e(α,β)^{Σr^i} * e(Σr^i*ICi,γ) * e(Z_C,δ).
+//
+// The verifier folds the commitments in GT with log(n) challenges, then combines all the final pairing checks
+// with random scalars into one pairing equation with a public target, which is checked by
+// `PairingProver` and `PairingVerifier`.
+use crate::{Groth16Verifier, RecursivePreparedVerifyingKey};
+use ark_bn254::{Bn254, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_groth16::Proof;
+use ark_relations::r1cs::{Result as R1CSResult, SynthesisError};
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::Rng;
+use ark_std::UniformRand;
+use on_proving_pairings::setup::PairingTarget;
+use sha2::{Digest, Sha256};
+
+type GT = PairingOutput<Bn254>;
+
+// The SRS of SnarkPack, from two powers of tau with the independent a and b.
+//
+// For up to N proofs, it holds [g^{a^i}], [g^{b^i}] for i in [0,2N), and [h^{a^i}], [h^{b^i}] for i in [0,N).
+// The keys of n <= N proofs are v = ([h^{a^i}], [h^{b^i}]) and w = ([g^{a^{n+i}}], [g^{b^{n+i}}]) for i in [0,n).
+pub struct SnarkPackSRS {
+    pub g_alpha_powers: Vec<G1Affine>,
+    pub g_beta_powers: Vec<G1Affine>,
+    pub h_alpha_powers: Vec<G2Affine>,
+    pub h_beta_powers: Vec<G2Affine>,
+}
+
+// The verifier part of the SRS, which only needs the first powers, so it verifies any n <= N proofs.
+pub struct VerifierSRS {
+    pub n: usize,
+    pub g: G1Affine,
+    pub h: G2Affine,
+    pub g_alpha: G1Affine,
+    pub g_beta: G1Affine,
+    pub h_alpha: G2Affine,
+    pub h_beta: G2Affine,
+}
+
+impl SnarkPackSRS {
+    // Setup with the known a and b, which is insecure and only for testing.
+    // In production, the powers should come from two independent powers of tau ceremonies.
+    pub fn setup_fake<R: Rng>(n: usize, rng: &mut R) -> Self {
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        Self {
+            g_alpha_powers: powers_of::<G1Projective>(a, 2 * n),
+            g_beta_powers: powers_of::<G1Projective>(b, 2 * n),
+            h_alpha_powers: powers_of::<G2Projective>(a, n),
+            h_beta_powers: powers_of::<G2Projective>(b, n),
+        }
+    }
+
+    // The maximum number of proofs it aggregates.
+    pub fn size(&self) -> usize {
+        self.h_alpha_powers.len()
+    }
+
+    pub fn verifier_srs(&self) -> VerifierSRS {
+        VerifierSRS {
+            n: self.size(),
+            g: self.g_alpha_powers[0],
+            h: self.h_alpha_powers[0],
+            g_alpha: self.g_alpha_powers[1],
+            g_beta: self.g_beta_powers[1],
+            h_alpha: self.h_alpha_powers[1],
+            h_beta: self.h_beta_powers[1],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AggregateProof {
+    // the commitments to [Ai],[Bi] and [Ci]
+    pub com_ab: (GT, GT),
+    pub com_c: (GT, GT),
+    // Z_AB = Π e(Ai,Bi)^{r^i}, Z_C = Π Ci^{r^i}
+    pub z_ab: GT,
+    pub z_c: G1Affine,
+    pub gipa: GipaProof,
+}
+
+// The GIPA proof of TIPP and MIPP, which share the challenges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GipaProof {
+    // the cross commitments and inner products (L, R) of each round
+    pub comms_ab: Vec<((GT, GT), (GT, GT))>,
+    pub comms_c: Vec<((GT, GT), (GT, GT))>,
+    pub z_ab: Vec<(GT, GT)>,
+    pub z_c: Vec<(G1Affine, G1Affine)>,
+    // the final folded values
+    pub final_a: G1Affine,
+    pub final_b: G2Affine,
+    pub final_c: G1Affine,
+    pub final_vkey: (G2Affine, G2Affine),
+    pub final_wkey: (G1Affine, G1Affine),
+    // the KZG openings of the final keys
+    pub vkey_opening: (G2Affine, G2Affine),
+    pub wkey_opening: (G1Affine, G1Affine),
+}
+
+// Aggregate the Groth16 proofs over the same vk.
+//
+// The number of proofs should be a power of two, and at most the size of srs.
+pub fn aggregate_proofs(
+    srs: &SnarkPackSRS,
+    proofs: &[Proof<Bn254>],
+    public_inputs: &[Vec<Fr>],
+) -> R1CSResult<AggregateProof> {
+    let n = proofs.len();
+    if n < 2 || !n.is_power_of_two() || n > srs.size() || public_inputs.len() != n {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+
+    let a = proofs.iter().map(|proof| proof.a).collect::<Vec<_>>();
+    let b = proofs.iter().map(|proof| proof.b).collect::<Vec<_>>();
+    let c = proofs.iter().map(|proof| proof.c).collect::<Vec<_>>();
+    let (v1, v2) = (&srs.h_alpha_powers[..n], &srs.h_beta_powers[..n]);
+    let (w1, w2) = (&srs.g_alpha_powers[n..2 * n], &srs.g_beta_powers[n..2 * n]);
+
+    let com_ab = (pair(&a, v1) + pair(w1, &b), pair(&a, v2) + pair(w2, &b));
+    let com_c = (pair(&c, v1), pair(&c, v2));
+
+    let mut transcript = Transcript::new(public_inputs);
+    transcript.append(&com_ab.0);
+    transcript.append(&com_ab.1);
+    transcript.append(&com_c.0);
+    transcript.append(&com_c.1);
+    let r = transcript.challenge();
+    let r_powers = powers(r, n);
+    let r_inv_powers = powers(r.inverse().unwrap(), n);
+
+    // rescale [Ai],[Ci] by r^i and v by r^{-i}, which keeps com_ab and com_c.
+    let a = scale::<G1Projective>(&a, &r_powers);
+    let c = scale::<G1Projective>(&c, &r_powers);
+    let v1 = scale::<G2Projective>(v1, &r_inv_powers);
+    let v2 = scale::<G2Projective>(v2, &r_inv_powers);
+
+    let z_ab = pair(&a, &b);
+    let z_c = c.iter().sum::<G1Projective>().into_affine();
+    transcript.append(&z_ab);
+    transcript.append(&z_c);
+
+    let gipa = prove_gipa(
+        srs,
+        &mut transcript,
+        r,
+        (a, b, c, vec![Fr::one(); n]),
+        (v1, v2),
+        (w1.to_vec(), w2.to_vec()),
+    );
+
+    Ok(AggregateProof {
+        com_ab,
+        com_c,
+        z_ab,
+        z_c,
+        gipa,
+    })
+}
+
+// GIPA of TIPP and MIPP: halve [Ai],[Bi],[Ci],[ui] and the keys in each round with the challenge x:
+//  A = A_L + x*A_R, B = B_L + x^{-1}*B_R, C = C_L + x*C_R, u = u_L + x^{-1}*u_R,
+//  v = v_L + x^{-1}*v_R, w = w_L + x*w_R
+// Then open the final keys at z with KZG.
+fn prove_gipa(
+    srs: &SnarkPackSRS,
+    transcript: &mut Transcript,
+    r: Fr,
+    (mut a, mut b, mut c, mut u): (Vec<G1Affine>, Vec<G2Affine>, Vec<G1Affine>, Vec<Fr>),
+    (mut v1, mut v2): (Vec<G2Affine>, Vec<G2Affine>),
+    (mut w1, mut w2): (Vec<G1Affine>, Vec<G1Affine>),
+) -> GipaProof {
+    let (mut comms_ab, mut comms_c, mut z_ab, mut z_c) = (vec![], vec![], vec![], vec![]);
+    let mut challenges = vec![];
+
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_l, a_r) = a.split_at(half);
+        let (b_l, b_r) = b.split_at(half);
+        let (c_l, c_r) = c.split_at(half);
+        let (u_l, u_r) = u.split_at(half);
+        let (v1_l, v1_r) = v1.split_at(half);
+        let (v2_l, v2_r) = v2.split_at(half);
+        let (w1_l, w1_r) = w1.split_at(half);
+        let (w2_l, w2_r) = w2.split_at(half);
+
+        let com_ab = (
+            (
+                pair(a_r, v1_l) + pair(w1_r, b_l),
+                pair(a_r, v2_l) + pair(w2_r, b_l),
+            ),
+            (
+                pair(a_l, v1_r) + pair(w1_l, b_r),
+                pair(a_l, v2_r) + pair(w2_l, b_r),
+            ),
+        );
+        let com_c = (
+            (pair(c_r, v1_l), pair(c_r, v2_l)),
+            (pair(c_l, v1_r), pair(c_l, v2_r)),
+        );
+        let z_ab_lr = (pair(a_r, b_l), pair(a_l, b_r));
+        let z_c_lr = (
+            G1Projective::msm(c_r, u_l).unwrap().into_affine(),
+            G1Projective::msm(c_l, u_r).unwrap().into_affine(),
+        );
+
+        transcript.append_round(&com_ab, &com_c, &z_ab_lr, &z_c_lr);
+        let x = transcript.challenge();
+        let x_inv = x.inverse().unwrap();
+
+        a = fold::<G1Projective>(&a, x);
+        b = fold::<G2Projective>(&b, x_inv);
+        c = fold::<G1Projective>(&c, x);
+        u = u_l
+            .iter()
+            .zip(u_r.iter())
+            .map(|(l, r)| *l + x_inv * r)
+            .collect();
+        v1 = fold::<G2Projective>(&v1, x_inv);
+        v2 = fold::<G2Projective>(&v2, x_inv);
+        w1 = fold::<G1Projective>(&w1, x);
+        w2 = fold::<G1Projective>(&w2, x);
+
+        comms_ab.push(com_ab);
+        comms_c.push(com_c);
+        z_ab.push(z_ab_lr);
+        z_c.push(z_c_lr);
+        challenges.push(x);
+    }
+
+    let final_vkey = (v1[0], v2[0]);
+    let final_wkey = (w1[0], w2[0]);
+    transcript.append_final_keys(&final_vkey, &final_wkey);
+    let z = transcript.challenge();
+
+    // the final keys are v = h^{f_v(a)}, w = g^{f_w(a)}
+    let challenges_inv = challenges
+        .iter()
+        .map(|x| x.inverse().unwrap())
+        .collect::<Vec<_>>();
+    let r_inv_powers = powers(r.inverse().unwrap(), 1 << challenges.len());
+    let fv = fold_poly(&challenges_inv)
+        .iter()
+        .zip(r_inv_powers.iter())
+        .map(|(coeff, r_inv)| *coeff * r_inv)
+        .collect::<Vec<_>>();
+    let fw = [vec![Fr::zero(); fv.len()], fold_poly(&challenges)].concat();
+
+    let n = fv.len();
+    let vkey_opening = (
+        kzg_open::<G2Projective>(&srs.h_alpha_powers[..n], &fv, z),
+        kzg_open::<G2Projective>(&srs.h_beta_powers[..n], &fv, z),
+    );
+    let wkey_opening = (
+        kzg_open::<G1Projective>(&srs.g_alpha_powers[..2 * n], &fw, z),
+        kzg_open::<G1Projective>(&srs.g_beta_powers[..2 * n], &fw, z),
+    );
+
+    GipaProof {
+        comms_ab,
+        comms_c,
+        z_ab,
+        z_c,
+        final_a: a[0],
+        final_b: b[0],
+        final_c: c[0],
+        final_vkey,
+        final_wkey,
+        vkey_opening,
+        wkey_opening,
+    }
+}
+
+// Verify the aggregate proof of Groth16 proofs over the same vk.
+//
+// All the final pairing checks are combined with the random scalars ρ0..ρ9 into
+//  e(-ρ0*Z_IC,-γ) * e(-ρ0*Z_C,-δ) * e(-ρ0*s*α,-β) * Π e(Pi,Qi) = Z_AB^ρ0 * Z_AB'^ρ1 * com_ab'^(ρ2,ρ3) * com_c'^(ρ4,ρ5)
+// where ' means folded, and it's checked by the pairing proof (c, wi) with the public target, without final exponentiation.
+pub fn verify_aggregate_proof<R: Rng>(
+    vsrs: &VerifierSRS,
+    rvk: &RecursivePreparedVerifyingKey,
+    public_inputs: &[Vec<Fr>],
+    proof: &AggregateProof,
+    rng: &mut R,
+) -> R1CSResult<bool> {
+    let n = public_inputs.len();
+    if n < 2 || !n.is_power_of_two() || n > vsrs.n {
+        return Err(SynthesisError::MalformedVerifyingKey);
+    }
+    let gipa = &proof.gipa;
+    let rounds = n.trailing_zeros() as usize;
+    if [
+        gipa.comms_ab.len(),
+        gipa.comms_c.len(),
+        gipa.z_ab.len(),
+        gipa.z_c.len(),
+    ]
+    .iter()
+    .any(|len| *len != rounds)
+    {
+        return Ok(false);
+    }
+
+    // redo the transcript
+    let mut transcript = Transcript::new(public_inputs);
+    transcript.append(&proof.com_ab.0);
+    transcript.append(&proof.com_ab.1);
+    transcript.append(&proof.com_c.0);
+    transcript.append(&proof.com_c.1);
+    let r = transcript.challenge();
+    transcript.append(&proof.z_ab);
+    transcript.append(&proof.z_c);
+    let challenges = (0..rounds)
+        .map(|j| {
+            transcript.append_round(
+                &gipa.comms_ab[j],
+                &gipa.comms_c[j],
+                &gipa.z_ab[j],
+                &gipa.z_c[j],
+            );
+            transcript.challenge()
+        })
+        .collect::<Vec<_>>();
+    transcript.append_final_keys(&gipa.final_vkey, &gipa.final_wkey);
+    let z = transcript.challenge();
+    let challenges_inv = challenges
+        .iter()
+        .map(|x| x.inverse().unwrap())
+        .collect::<Vec<_>>();
+
+    // fold the commitments and the inner products with the challenges
+    let (mut com_ab, mut com_c, mut z_ab) = (proof.com_ab, proof.com_c, proof.z_ab);
+    let mut z_c = proof.z_c.into_group();
+    for (j, (x, x_inv)) in challenges.iter().zip(challenges_inv.iter()).enumerate() {
+        let ((ab_l, ab_r), (c_l, c_r)) = (gipa.comms_ab[j], gipa.comms_c[j]);
+        com_ab.0 += ab_l.0 * x + ab_r.0 * x_inv;
+        com_ab.1 += ab_l.1 * x + ab_r.1 * x_inv;
+        com_c.0 += c_l.0 * x + c_r.0 * x_inv;
+        com_c.1 += c_l.1 * x + c_r.1 * x_inv;
+        z_ab += gipa.z_ab[j].0 * x + gipa.z_ab[j].1 * x_inv;
+        z_c += gipa.z_c[j].0 * x + gipa.z_c[j].1 * x_inv;
+    }
+
+    // MIPP: Z_C' = C' * u', where u' = Π (1 + x_j^{-1}) is the folded [1,...,1]
+    if z_c != gipa.final_c * eval_fold_poly(&challenges_inv, Fr::one()) {
+        return Ok(false);
+    }
+
+    // Groth16: Z_IC = Σ r^i*ICi, s = Σ r^i
+    let r_powers = powers(r, n);
+    let prepared_inputs = public_inputs
+        .iter()
+        .map(|pi| Groth16Verifier::prepare_inputs(&rvk.pvk, pi))
+        .collect::<R1CSResult<Vec<_>>>()?;
+    let z_ic =
+        G1Projective::msm(&G1Projective::normalize_batch(&prepared_inputs), &r_powers).unwrap();
+    let s = r_powers.iter().sum::<Fr>();
+
+    // KZG: f_v(z) = Π (1 + x_j^{-1} * (z/r)^{n/2^j}), f_w(z) = z^n * Π (1 + x_j * z^{n/2^j})
+    let fv_z = eval_fold_poly(&challenges_inv, z * r.inverse().unwrap());
+    let fw_z = z.pow([n as u64]) * eval_fold_poly(&challenges, z);
+
+    let rho = (0..10).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+    let g = vsrs.g.into_group();
+    let h = vsrs.h.into_group();
+    let (v1, v2) = gipa.final_vkey;
+    let (w1, w2) = gipa.final_wkey;
+
+    // fixed [-γ,-δ,-β] of rvk
+    let eval_points = G1Projective::normalize_batch(&[
+        -(z_ic * rho[0]),
+        -(proof.z_c * rho[0]),
+        -(rvk.pvk.vk.alpha_g1 * (s * rho[0])),
+    ]);
+    let (non_fixed_p, non_fixed_q): (Vec<_>, Vec<_>) = [
+        // TIPP and the pair commitments on B'
+        (
+            gipa.final_a * rho[1] + w1 * rho[2] + w2 * rho[3],
+            gipa.final_b.into_group(),
+        ),
+        // the commitments on v', with e(-g,v') of KZG
+        (
+            gipa.final_a * rho[2] + gipa.final_c * rho[4] - g * rho[6],
+            v1.into_group(),
+        ),
+        (
+            gipa.final_a * rho[3] + gipa.final_c * rho[5] - g * rho[7],
+            v2.into_group(),
+        ),
+        // KZG of v': e(g^{a-z},π) * e(-g,v') * e(g^{f_v(z)},h) = 1
+        (
+            (vsrs.g_alpha.into_group() - g * z) * rho[6],
+            gipa.vkey_opening.0.into_group(),
+        ),
+        (
+            (vsrs.g_beta.into_group() - g * z) * rho[7],
+            gipa.vkey_opening.1.into_group(),
+        ),
+        // KZG of w': e(π,h^{a-z}) * e(g^{f_w(z)}-w',h) = 1
+        (
+            gipa.wkey_opening.0 * rho[8],
+            vsrs.h_alpha.into_group() - h * z,
+        ),
+        (
+            gipa.wkey_opening.1 * rho[9],
+            vsrs.h_beta.into_group() - h * z,
+        ),
+        (
+            g * (fv_z * (rho[6] + rho[7]) + fw_z * (rho[8] + rho[9])) - w1 * rho[8] - w2 * rho[9],
+            h,
+        ),
+    ]
+    .into_iter()
+    .unzip();
+    let non_fixed_p = G1Projective::normalize_batch(&non_fixed_p);
+    let non_fixed_q = G2Projective::normalize_batch(&non_fixed_q);
+
+    let target = proof.z_ab * rho[0]
+        + z_ab * rho[1]
+        + com_ab.0 * rho[2]
+        + com_ab.1 * rho[3]
+        + com_c.0 * rho[4]
+        + com_c.1 * rho[5];
+    // the target outside GT, e.g. zero, means the proof is malformed.
+    let target = match PairingTarget::new(target) {
+        Ok(target) => target,
+        Err(_) => return Ok(false),
+    };
+
+    Groth16Verifier::prove_and_verify_pairing(
+        &rvk.pairing_pvk,
+        eval_points,
+        non_fixed_p,
+        non_fixed_q,
+        Some(&target),
+    )
+}
+
+// Fiat-Shamir transcript with sha256.
+struct Transcript {
+    hasher: Sha256,
+}
+
+impl Transcript {
+    fn new(public_inputs: &[Vec<Fr>]) -> Self {
+        let mut transcript = Self {
+            hasher: Sha256::new_with_prefix(b"snarkpack"),
+        };
+        for pi in public_inputs {
+            transcript.append(pi);
+        }
+        transcript
+    }
+
+    fn append<T: CanonicalSerialize>(&mut self, item: &T) {
+        let mut bytes = vec![];
+        item.serialize_compressed(&mut bytes).unwrap();
+        self.hasher.update(bytes);
+    }
+
+    fn append_round(
+        &mut self,
+        com_ab: &((GT, GT), (GT, GT)),
+        com_c: &((GT, GT), (GT, GT)),
+        z_ab: &(GT, GT),
+        z_c: &(G1Affine, G1Affine),
+    ) {
+        for com in [com_ab, com_c] {
+            for item in [com.0 .0, com.0 .1, com.1 .0, com.1 .1] {
+                self.append(&item);
+            }
+        }
+        self.append(&z_ab.0);
+        self.append(&z_ab.1);
+        self.append(&z_c.0);
+        self.append(&z_c.1);
+    }
+
+    fn append_final_keys(&mut self, vkey: &(G2Affine, G2Affine), wkey: &(G1Affine, G1Affine)) {
+        self.append(&vkey.0);
+        self.append(&vkey.1);
+        self.append(&wkey.0);
+        self.append(&wkey.1);
+    }
+
+    // the non-zero challenge, which is absorbed back.
+    fn challenge(&mut self) -> Fr {
+        loop {
+            let hash = self.hasher.clone().finalize();
+            self.hasher.update(hash);
+            let challenge = Fr::from_le_bytes_mod_order(&hash);
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+}
+
+// Π e(ai,bi)
+fn pair(a: &[G1Affine], b: &[G2Affine]) -> GT {
+    Bn254::multi_pairing(a.iter().copied(), b.iter().copied())
+}
+
+// [1, s, s^2, ..., s^{len-1}]
+fn powers(s: Fr, len: usize) -> Vec<Fr> {
+    std::iter::successors(Some(Fr::one()), |acc| Some(*acc * s))
+        .take(len)
+        .collect()
+}
+
+// [G^{s^i}] for i in [0,len)
+fn powers_of<G: CurveGroup<ScalarField = Fr>>(s: Fr, len: usize) -> Vec<G::Affine> {
+    let g = G::generator();
+    G::normalize_batch(&powers(s, len).iter().map(|si| g * si).collect::<Vec<_>>())
+}
+
+// [Pi * si]
+fn scale<G: CurveGroup<ScalarField = Fr>>(points: &[G::Affine], scalars: &[Fr]) -> Vec<G::Affine> {
+    G::normalize_batch(
+        &points
+            .iter()
+            .zip(scalars.iter())
+            .map(|(p, s)| *p * s)
+            .collect::<Vec<_>>(),
+    )
+}
+
+// [L + x*R], where [L|R] = points
+fn fold<G: CurveGroup<ScalarField = Fr>>(points: &[G::Affine], x: Fr) -> Vec<G::Affine> {
+    let (l, r) = points.split_at(points.len() / 2);
+    G::normalize_batch(
+        &l.iter()
+            .zip(r.iter())
+            .map(|(l, r)| *r * x + l)
+            .collect::<Vec<_>>(),
+    )
+}
+
+// The coefficients of Π_j (1 + x_j * X^{n/2^{j+1}}) with n = 2^{len}, which is the folded [1,...,1]:
+//  the coefficient of X^i is the product of x_j, where the bit n/2^{j+1} of i is set.
+fn fold_poly(challenges: &[Fr]) -> Vec<Fr> {
+    let n = 1 << challenges.len();
+    (0..n)
+        .map(|i| {
+            challenges
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| i & (n >> (j + 1)) != 0)
+                .map(|(_, x)| *x)
+                .product()
+        })
+        .collect()
+}
+
+// Evaluate Π_j (1 + x_j * z^{n/2^{j+1}}) in log(n).
+fn eval_fold_poly(challenges: &[Fr], z: Fr) -> Fr {
+    let n = 1u64 << challenges.len();
+    challenges
+        .iter()
+        .enumerate()
+        .map(|(j, x)| Fr::one() + *x * z.pow([n >> (j + 1)]))
+        .product()
+}
+
+// KZG opening of f at z: π = G^{q(s)}, where q(X) = (f(X) - f(z)) / (X - z) by the synthetic division.
+fn kzg_open<G: CurveGroup<ScalarField = Fr>>(
+    powers: &[G::Affine],
+    poly: &[Fr],
+    z: Fr,
+) -> G::Affine {
+    let mut quotient = vec![Fr::zero(); poly.len() - 1];
+    let mut acc = Fr::zero();
+    for i in (1..poly.len()).rev() {
+        acc = acc * z + poly[i];
+        quotient[i - 1] = acc;
+    }
+    G::msm(&powers[..quotient.len()], &quotient)
+        .unwrap()
+        .into_affine()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dummy_circuit::{gen_groth16_dummy_circuit_proof, gen_groth16_dummy_circuit_proofs};
+    use ark_groth16::Groth16;
+    use ark_std::test_rng;
+
+    #[test]
+    fn test_fold_poly() {
+        let mut rng = test_rng();
+        let challenges = (0..3).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let z = Fr::rand(&mut rng);
+
+        // folding [1,...,1] gives the coefficients
+        let mut ones = vec![Fr::one(); 8];
+        for x in challenges.iter() {
+            let (l, r) = ones.split_at(ones.len() / 2);
+            ones = l.iter().zip(r.iter()).map(|(l, r)| *l + *x * r).collect();
+        }
+        let coeffs = fold_poly(&challenges);
+        assert_eq!(ones[0], coeffs.iter().sum::<Fr>());
+
+        let eval = coeffs
+            .iter()
+            .zip(powers(z, 8))
+            .map(|(c, zi)| *c * zi)
+            .sum::<Fr>();
+        assert_eq!(eval, eval_fold_poly(&challenges, z));
+    }
+
+    #[test]
+    fn test_aggregate_proofs() {
+        let mut rng = test_rng();
+        let n = 8;
+        let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<Bn254>(6);
+        let rvk = RecursivePreparedVerifyingKey::new(&pvk.vk).unwrap();
+        let srs = SnarkPackSRS::setup_fake(n, &mut rng);
+        let vsrs = srs.verifier_srs();
+
+        let proofs = (0..n)
+            .map(|_| Groth16::<Bn254>::rerandomize_proof(&pvk.vk, &proof, &mut rng))
+            .collect::<Vec<_>>();
+        let public_inputs = vec![pi.clone(); n];
+
+        let aggregate = aggregate_proofs(&srs, &proofs, &public_inputs).unwrap();
+        assert_eq!(aggregate.gipa.comms_ab.len(), 3);
+        assert!(verify_aggregate_proof(&vsrs, &rvk, &public_inputs, &aggregate, &mut rng).unwrap());
+
+        // wrong public inputs
+        let mut wrong_inputs = public_inputs.clone();
+        wrong_inputs[5] = vec![pi[0] + pi[0]];
+        assert!(!verify_aggregate_proof(&vsrs, &rvk, &wrong_inputs, &aggregate, &mut rng).unwrap());
+
+        // tampered aggregate proof
+        let mut tampered = aggregate.clone();
+        tampered.z_ab = tampered.z_ab + tampered.z_ab;
+        assert!(!verify_aggregate_proof(&vsrs, &rvk, &public_inputs, &tampered, &mut rng).unwrap());
+        let mut tampered = aggregate.clone();
+        tampered.gipa.final_a = (tampered.gipa.final_a + tampered.gipa.final_a).into();
+        assert!(!verify_aggregate_proof(&vsrs, &rvk, &public_inputs, &tampered, &mut rng).unwrap());
+        let mut tampered = aggregate.clone();
+        tampered.gipa.z_c.pop();
+        assert!(!verify_aggregate_proof(&vsrs, &rvk, &public_inputs, &tampered, &mut rng).unwrap());
+
+        // one invalid proof
+        let mut invalid_proofs = proofs.clone();
+        invalid_proofs[3].c = (invalid_proofs[3].c + invalid_proofs[3].c).into();
+        let aggregate = aggregate_proofs(&srs, &invalid_proofs, &public_inputs).unwrap();
+        assert!(
+            !verify_aggregate_proof(&vsrs, &rvk, &public_inputs, &aggregate, &mut rng).unwrap()
+        );
+
+        // the number of proofs doesn't fit the srs
+        assert!(aggregate_proofs(&srs, &proofs[..6], &public_inputs[..6]).is_err());
+        let proofs = [proofs.clone(), proofs].concat();
+        let public_inputs = vec![pi; 2 * n];
+        assert!(aggregate_proofs(&srs, &proofs, &public_inputs).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_proofs_smaller_than_srs() {
+        let mut rng = test_rng();
+        // independent proofs with distinct public inputs
+        let (proofs, pvk, public_inputs) = gen_groth16_dummy_circuit_proofs::<Bn254>(6, 4);
+        let rvk = RecursivePreparedVerifyingKey::new(&pvk.vk).unwrap();
+        let srs = SnarkPackSRS::setup_fake(16, &mut rng);
+        let vsrs = srs.verifier_srs();
+
+        for n in [2, 4] {
+            let aggregate = aggregate_proofs(&srs, &proofs[..n], &public_inputs[..n]).unwrap();
+            assert_eq!(aggregate.gipa.comms_ab.len(), n.trailing_zeros() as usize);
+            assert!(
+                verify_aggregate_proof(&vsrs, &rvk, &public_inputs[..n], &aggregate, &mut rng)
+                    .unwrap()
+            );
+
+            // the public inputs in another order
+            let mut swapped = public_inputs[..n].to_vec();
+            swapped.swap(0, 1);
+            assert!(!verify_aggregate_proof(&vsrs, &rvk, &swapped, &aggregate, &mut rng).unwrap());
+        }
+
+        // an aggregate of 2 proofs doesn't pass as that of 4
+        let aggregate = aggregate_proofs(&srs, &proofs[..2], &public_inputs[..2]).unwrap();
+        assert!(
+            !verify_aggregate_proof(&vsrs, &rvk, &public_inputs, &aggregate, &mut rng).unwrap()
+        );
+    }
+}
@@ -1,8 +1,17 @@
-use crate::Groth16Verifier;
+use crate::{Groth16Verifier, RecursivePreparedVerifyingKey};
 
-use crate::dummy_circuit::gen_groth16_dummy_circuit_proof;
-use ark_bn254::Bn254;
-use ark_groth16::Groth16;
+use crate::dummy_circuit::{
+    gen_groth16_dummy_circuit_proof, gen_groth16_dummy_circuit_proofs,
+    gen_groth16_dummy_circuit_proofs_with_seed,
+};
+use ark_bn254::{Bn254, Fr};
+use ark_ec::bn::G2Prepared;
+use ark_ec::pairing::PairingOutput;
+use ark_ff::{Field, One};
+use ark_groth16::{prepare_verifying_key, Groth16};
+use ark_relations::r1cs::SynthesisError;
+use ark_std::{test_rng, UniformRand};
+use on_proving_pairings::setup::PairingTarget;
 
 #[test]
 fn test_groth16_verifier() {
@@ -23,3 +32,320 @@ fn test_groth16_verifier() {
     assert!(Groth16Verifier::verify_proof(&pvk, &proof, &pi, false).unwrap());
     assert!(Groth16Verifier::verify_proof(&pvk, &proof, &pi, true).unwrap());
 }
+
+#[test]
+fn test_groth16_verifier_with_invalid_proof() {
+    type E = Bn254;
+
+    let k = 6;
+    let (mut proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<E>(k);
+    proof.a = (proof.a + proof.a).into();
+
+    assert!(!Groth16::<E>::verify_proof(&pvk, &proof, &pi).unwrap());
+    assert!(!Groth16Verifier::verify_proof(&pvk, &proof, &pi, false).unwrap());
+    assert!(!Groth16Verifier::verify_proof(&pvk, &proof, &pi, true).unwrap());
+
+    // wrong length of public inputs
+    assert!(Groth16Verifier::verify_proof(&pvk, &proof, &[], true).is_err());
+}
+
+#[test]
+fn test_groth16_verifier_with_target() {
+    type E = Bn254;
+
+    for k in [1, 6] {
+        let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<E>(k);
+        let target = Groth16Verifier::prepare_target(&pvk).unwrap();
+        let prepared_inputs = Groth16Verifier::prepare_inputs(&pvk, &pi).unwrap();
+
+        // cross-check with the native verifier
+        assert!(Groth16::<E>::verify_proof(&pvk, &proof, &pi).unwrap());
+        assert!(
+            Groth16Verifier::verify_proof_with_target(&pvk, &target, &proof, &prepared_inputs)
+                .unwrap()
+        );
+
+        // invalid proof
+        let mut invalid = proof.clone();
+        invalid.c = (invalid.c + invalid.c).into();
+        assert!(!Groth16::<E>::verify_proof(&pvk, &invalid, &pi).unwrap());
+        assert!(!Groth16Verifier::verify_proof_with_target(
+            &pvk,
+            &target,
+            &invalid,
+            &prepared_inputs
+        )
+        .unwrap());
+
+        // wrong public inputs
+        let wrong_inputs =
+            Groth16Verifier::prepare_inputs(&pvk, &vec![pi[0] + pi[0]; pi.len()]).unwrap();
+        assert!(!Groth16::<E>::verify_proof(&pvk, &proof, &vec![pi[0] + pi[0]; pi.len()]).unwrap());
+        assert!(
+            !Groth16Verifier::verify_proof_with_target(&pvk, &target, &proof, &wrong_inputs)
+                .unwrap()
+        );
+    }
+
+    // wrong target, e.g. e(α,β)^2
+    let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<E>(6);
+    let prepared_inputs = Groth16Verifier::prepare_inputs(&pvk, &pi).unwrap();
+    let wrong_target = PairingTarget::new(PairingOutput(pvk.alpha_g1_beta_g2.square())).unwrap();
+    assert!(!Groth16Verifier::verify_proof_with_target(
+        &pvk,
+        &wrong_target,
+        &proof,
+        &prepared_inputs
+    )
+    .unwrap());
+}
+
+#[test]
+fn test_groth16_verifier_with_prepared_vk() {
+    type E = Bn254;
+
+    let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<E>(6);
+    let rvk = RecursivePreparedVerifyingKey::new(&pvk.vk).unwrap();
+
+    // the fixed lines are in the order of [-γ,-δ,-β], which the prover consumes.
+    let expected: Vec<G2Prepared<ark_bn254::Config>> = vec![
+        (-pvk.vk.gamma_g2).into(),
+        (-pvk.vk.delta_g2).into(),
+        (-pvk.vk.beta_g2).into(),
+    ];
+    assert_eq!(rvk.pairing_pvk.lines, expected);
+    assert_eq!(rvk.target_pvk.lines, expected[..2]);
+    assert_eq!(
+        rvk.target.f,
+        Groth16Verifier::prepare_target(&pvk).unwrap().f
+    );
+
+    // cross-check with the native verifier
+    let prepared_inputs = Groth16Verifier::prepare_inputs(&rvk.pvk, &pi).unwrap();
+    assert!(Groth16::<E>::verify_proof(&pvk, &proof, &pi).unwrap());
+    assert!(
+        Groth16Verifier::verify_proof_with_prepared_vk(&rvk, &proof, &prepared_inputs).unwrap()
+    );
+    assert!(Groth16Verifier::verify_proof_with_prepared_vk_and_target(
+        &rvk,
+        &proof,
+        &prepared_inputs
+    )
+    .unwrap());
+
+    // invalid proof
+    let mut invalid = proof.clone();
+    invalid.c = (invalid.c + invalid.c).into();
+    assert!(!Groth16::<E>::verify_proof(&pvk, &invalid, &pi).unwrap());
+    assert!(
+        !Groth16Verifier::verify_proof_with_prepared_vk(&rvk, &invalid, &prepared_inputs).unwrap()
+    );
+    assert!(!Groth16Verifier::verify_proof_with_prepared_vk_and_target(
+        &rvk,
+        &invalid,
+        &prepared_inputs
+    )
+    .unwrap());
+
+    // malformed e(α,β), which isn't in GT
+    for alpha_g1_beta_g2 in [
+        ark_bn254::Fq12::ZERO,
+        pvk.alpha_g1_beta_g2 + ark_bn254::Fq12::ONE,
+    ] {
+        let mut malformed = pvk.clone();
+        malformed.alpha_g1_beta_g2 = alpha_g1_beta_g2;
+        assert!(matches!(
+            RecursivePreparedVerifyingKey::try_from(malformed),
+            Err(SynthesisError::MalformedVerifyingKey)
+        ));
+    }
+}
+
+#[test]
+fn test_groth16_batch_verify() {
+    type E = Bn254;
+
+    let mut rng = test_rng();
+    let (proof, pvk, pi) = gen_groth16_dummy_circuit_proof::<E>(6);
+    let rvk = RecursivePreparedVerifyingKey::new(&pvk.vk).unwrap();
+
+    // rerandomized proofs of the same statement
+    let proofs = (0..4)
+        .map(|_| Groth16::<E>::rerandomize_proof(&pvk.vk, &proof, &mut rng))
+        .collect::<Vec<_>>();
+    let public_inputs = vec![pi.clone(); proofs.len()];
+    for proof in proofs.iter() {
+        assert!(Groth16::<E>::verify_proof(&pvk, proof, &pi).unwrap());
+    }
+    assert!(Groth16Verifier::batch_verify(&rvk, &proofs, &public_inputs, &mut rng).unwrap());
+    assert!(Groth16Verifier::batch_verify(&rvk, &[], &[], &mut rng).unwrap());
+
+    // a batch containing one invalid proof
+    let mut invalid_proofs = proofs.clone();
+    invalid_proofs[2].c = (invalid_proofs[2].c + invalid_proofs[2].c).into();
+    assert!(!Groth16::<E>::verify_proof(&pvk, &invalid_proofs[2], &pi).unwrap());
+    assert!(
+        !Groth16Verifier::batch_verify(&rvk, &invalid_proofs, &public_inputs, &mut rng).unwrap()
+    );
+
+    // a batch containing one wrong public input
+    let mut wrong_inputs = public_inputs.clone();
+    wrong_inputs[1] = vec![pi[0] + pi[0]];
+    assert!(!Groth16Verifier::batch_verify(&rvk, &proofs, &wrong_inputs, &mut rng).unwrap());
+
+    // mismatched lengths
+    assert!(matches!(
+        Groth16Verifier::batch_verify(&rvk, &proofs, &public_inputs[1..], &mut rng),
+        Err(SynthesisError::MalformedVerifyingKey)
+    ));
+}
+
+#[test]
+fn test_groth16_batch_verify_independent_proofs() {
+    type E = Bn254;
+
+    let mut rng = test_rng();
+    // independent proofs over the same vk, whose public inputs all differ
+    let (proofs, pvk, public_inputs) = gen_groth16_dummy_circuit_proofs::<E>(6, 4);
+    let rvk = RecursivePreparedVerifyingKey::new(&pvk.vk).unwrap();
+    for (i, (proof, pi)) in proofs.iter().zip(public_inputs.iter()).enumerate() {
+        assert!(Groth16::<E>::verify_proof(&pvk, proof, pi).unwrap());
+        assert!(public_inputs[..i].iter().all(|other| other != pi));
+    }
+    assert!(Groth16Verifier::batch_verify(&rvk, &proofs, &public_inputs, &mut rng).unwrap());
+
+    // the public inputs are swapped between two proofs
+    let mut swapped = public_inputs.clone();
+    swapped.swap(0, 3);
+    assert!(!Groth16Verifier::batch_verify(&rvk, &proofs, &swapped, &mut rng).unwrap());
+
+    // one invalid proof in the batch
+    let mut invalid_proofs = proofs.clone();
+    invalid_proofs[1].a = (invalid_proofs[1].a + invalid_proofs[1].a).into();
+    assert!(
+        !Groth16Verifier::batch_verify(&rvk, &invalid_proofs, &public_inputs, &mut rng).unwrap()
+    );
+}
+
+#[test]
+fn test_groth16_batch_verify_heterogeneous() {
+    type E = Bn254;
+
+    let mut rng = test_rng();
+    let (proof1, pvk1, pi1) = gen_groth16_dummy_circuit_proof::<E>(1);
+    let (proof2, pvk2, pi2) = gen_groth16_dummy_circuit_proof::<E>(6);
+
+    // another circuit with a different δ: δ' = δ/s, C' = s*C
+    let s = Fr::rand(&mut rng);
+    let mut vk3 = pvk2.vk.clone();
+    vk3.delta_g2 = (vk3.delta_g2 * s.inverse().unwrap()).into();
+    let mut proof3 = proof2.clone();
+    proof3.c = (proof3.c * s).into();
+    assert!(Groth16::<E>::verify_proof(&prepare_verifying_key(&vk3), &proof3, &pi2).unwrap());
+
+    let rvk1 = RecursivePreparedVerifyingKey::new(&pvk1.vk).unwrap();
+    let rvk2 = RecursivePreparedVerifyingKey::new(&pvk2.vk).unwrap();
+    let rvk3 = RecursivePreparedVerifyingKey::new(&vk3).unwrap();
+    let batch = vec![
+        (&rvk1, &proof1, pi1.as_slice()),
+        (&rvk2, &proof2, pi2.as_slice()),
+        (&rvk3, &proof3, pi2.as_slice()),
+        (&rvk1, &proof1, pi1.as_slice()),
+    ];
+
+    // the dummy circuits share the setup randomness, so only δ' adds a new G2 point.
+    let prepared_inputs = batch
+        .iter()
+        .map(|(rvk, _, pi)| Groth16Verifier::prepare_inputs(&rvk.pvk, pi).unwrap())
+        .collect::<Vec<_>>();
+    let scalars = vec![Fr::one(); batch.len()];
+    let (pairing_pvk, eval_points) =
+        Groth16Verifier::merge_fixed_pairs(&batch, &prepared_inputs, &scalars);
+    assert_eq!(pairing_pvk.lines.len(), 4);
+    assert_eq!(eval_points.len(), 4);
+
+    assert!(
+        Groth16Verifier::batch_verify_heterogeneous(&batch, &mut rng)
+            .unwrap()
+            .is_empty()
+    );
+    assert!(Groth16Verifier::batch_verify_heterogeneous(&[], &mut rng)
+        .unwrap()
+        .is_empty());
+
+    // proofs against the wrong vk, or with an invalid C
+    let mut invalid = proof1.clone();
+    invalid.c = (invalid.c + invalid.c).into();
+    let mut invalid_batch = batch.clone();
+    invalid_batch[1] = (&rvk3, &proof2, pi2.as_slice());
+    invalid_batch[3] = (&rvk1, &invalid, pi1.as_slice());
+    assert_eq!(
+        Groth16Verifier::batch_verify_heterogeneous(&invalid_batch, &mut rng).unwrap(),
+        vec![1, 3]
+    );
+}
+
+#[test]
+fn test_groth16_batch_verify_heterogeneous_unrelated_vks() {
+    type E = Bn254;
+
+    let mut rng = test_rng();
+    // separate setups, so no G2 point is shared among the vks
+    let (proofs1, pvk1, pis1) = gen_groth16_dummy_circuit_proofs_with_seed::<E>(1, 2, 1);
+    let (proofs2, pvk2, pis2) = gen_groth16_dummy_circuit_proofs_with_seed::<E>(6, 1, 2);
+    let (proofs3, pvk3, pis3) = gen_groth16_dummy_circuit_proofs_with_seed::<E>(3, 1, 3);
+
+    let rvk1 = RecursivePreparedVerifyingKey::new(&pvk1.vk).unwrap();
+    let rvk2 = RecursivePreparedVerifyingKey::new(&pvk2.vk).unwrap();
+    let rvk3 = RecursivePreparedVerifyingKey::new(&pvk3.vk).unwrap();
+    let batch = vec![
+        (&rvk1, &proofs1[0], pis1[0].as_slice()),
+        (&rvk2, &proofs2[0], pis2[0].as_slice()),
+        (&rvk1, &proofs1[1], pis1[1].as_slice()),
+        (&rvk3, &proofs3[0], pis3[0].as_slice()),
+    ];
+
+    // 3 vks with distinct [-γ,-δ,-β]
+    let prepared_inputs = batch
+        .iter()
+        .map(|(rvk, _, pi)| Groth16Verifier::prepare_inputs(&rvk.pvk, pi).unwrap())
+        .collect::<Vec<_>>();
+    let scalars = vec![Fr::one(); batch.len()];
+    let (pairing_pvk, eval_points) =
+        Groth16Verifier::merge_fixed_pairs(&batch, &prepared_inputs, &scalars);
+    assert_eq!(pairing_pvk.lines.len(), 9);
+    assert_eq!(eval_points.len(), 9);
+
+    assert!(
+        Groth16Verifier::batch_verify_heterogeneous(&batch, &mut rng)
+            .unwrap()
+            .is_empty()
+    );
+
+    // a proof against an unrelated vk
+    let mut invalid_batch = batch.clone();
+    invalid_batch[3] = (&rvk2, &proofs3[0], pis3[0].as_slice());
+    assert_eq!(
+        Groth16Verifier::batch_verify_heterogeneous(&invalid_batch, &mut rng).unwrap(),
+        vec![3]
+    );
+
+    // a wrong number of public inputs fails alone, and the rest are still batched
+    let too_many = vec![pis2[0][0]; 2];
+    let mut mixed_batch = batch.clone();
+    mixed_batch[1] = (&rvk2, &proofs2[0], too_many.as_slice());
+    assert_eq!(
+        Groth16Verifier::batch_verify_heterogeneous(&mixed_batch, &mut rng).unwrap(),
+        vec![1]
+    );
+    mixed_batch[2] = (&rvk1, &proofs1[1], &[]);
+    mixed_batch[3] = (&rvk3, &proofs1[0], pis1[0].as_slice());
+    assert_eq!(
+        Groth16Verifier::batch_verify_heterogeneous(&mixed_batch, &mut rng).unwrap(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        Groth16Verifier::batch_verify_heterogeneous(&mixed_batch[1..3], &mut rng).unwrap(),
+        vec![0, 1]
+    );
+}
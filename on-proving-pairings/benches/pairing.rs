@@ -0,0 +1,80 @@
+#![allow(non_snake_case)]
+
+use ark_bn254::{Bn254, Fq12, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, Group};
+use ark_ff::Field;
+use ark_std::UniformRand;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use on_proving_pairings::lambda_residues::LambdaResidues;
+use on_proving_pairings::params;
+use on_proving_pairings::prover::PairingProver;
+use on_proving_pairings::setup::PairingPVKey;
+use on_proving_pairings::verifier::PairingVerifier;
+use std::ops::Mul;
+
+// sample n pairs satisfying `e(P1,Q1)*...*e(Pn,Qn)=1`
+fn sample_pairing_equation(n: usize) -> (Vec<G1Affine>, Vec<G2Affine>) {
+    let rng = &mut ark_std::test_rng();
+    let mut P = vec![];
+    let mut Q = vec![];
+    let mut acc = Fr::from(0);
+    for _ in 0..n - 1 {
+        let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+        acc += a * b;
+        P.push(G1Projective::generator().mul(a).into_affine());
+        Q.push(G2Projective::generator().mul(b).into_affine());
+    }
+    P.push(G1Projective::generator().mul(-acc).into_affine());
+    Q.push(G2Projective::generator().into_affine());
+
+    (P, Q)
+}
+
+// c^{p^i} by square-and-multiply vs. the i-th frobenius map.
+fn bench_frobenius(c: &mut Criterion) {
+    let rng = &mut ark_std::test_rng();
+    let f = Fq12::rand(rng);
+
+    let mut group = c.benchmark_group("c^{p^i}");
+    for i in 1..4 {
+        let p_pow_i = params::MODULUS.pow(i as u32).to_u64_digits();
+        group.bench_with_input(BenchmarkId::new("pow", i), &i, |b, _| {
+            b.iter(|| f.pow(&p_pow_i))
+        });
+        group.bench_with_input(BenchmarkId::new("frobenius_map", i), &i, |b, &i| {
+            b.iter(|| f.frobenius_map(i))
+        });
+    }
+    group.finish();
+}
+
+// c^lambda = f * wi, where f is the output of the miller loop
+fn bench_finding_c(c: &mut Criterion) {
+    let (P, Q) = sample_pairing_equation(4);
+    let f = Bn254::multi_miller_loop(P, Q).0;
+
+    c.bench_function("finding_c", |b| b.iter(|| LambdaResidues::finding_c(f)));
+}
+
+// e(P1,Q1)*e(P2,Q2)*e(P3,Q3)*e(P4,Q4)=1, where Q1,Q2,Q3 are fixed.
+fn bench_prove_and_verify(c: &mut Criterion) {
+    let (P, Q) = sample_pairing_equation(4);
+    let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..3].to_vec());
+    let proof = PairingProver::prove(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]]).unwrap();
+
+    c.bench_function("prove", |b| {
+        b.iter(|| PairingProver::prove(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]]))
+    });
+    c.bench_function("verify", |b| {
+        b.iter(|| PairingVerifier::verify(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]], &proof))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_frobenius,
+    bench_finding_c,
+    bench_prove_and_verify
+);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+// Derive the λ residues parameters of a BN curve from the BN parameter x, and print them as Rust source.
+//
+// Usage: cargo run --bin bn_params -- <x> [xi] [fields]
+//  @x: the BN parameter in decimal, e.g. 4965661367192848881 for BN254
+//  @xi: ξ = xi + u, the smallest one which is neither square nor cube in Fp2 by default
+//  @fields: the module of Fq, Fq2, Fq6 and Fq12, `super` by default, e.g. `ark_bn254`
+use num_bigint::BigInt;
+use on_proving_pairings::params_gen::BnParams;
+use std::process::exit;
+use std::str::FromStr;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.len() > 3 {
+        eprintln!("usage: bn_params <x> [xi] [fields]");
+        exit(1);
+    }
+    let x = BigInt::from_str(&args[0]).unwrap_or_else(|_| {
+        eprintln!("x should be a decimal integer: {}", args[0]);
+        exit(1);
+    });
+    let fields = args.get(2).map_or("super", String::as_str);
+    let xi = args.get(1).map(|xi| {
+        u64::from_str(xi).unwrap_or_else(|_| {
+            eprintln!("xi should be a u64: {}", xi);
+            exit(1);
+        })
+    });
+
+    match BnParams::from_x(&x, xi) {
+        Ok(params) => print!("{}", params.to_rust_source(fields)),
+        Err(err) => {
+            eprintln!("x = {} is rejected: {}", x, err);
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use ark_ec::bls12::{Bls12Config, G1Affine, G2Affine, TwistType};
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ec::AffineRepr;
+use ark_ff::fields::fp12_2over3over2::Fp12;
+use ark_ff::{Field, Fp2};
+
+// Coefficients of the line evaluations, which are the same as `G2Prepared::ell_coeffs`.
+pub type EllCoeff<P> = (
+    Fp2<<P as Bls12Config>::Fp2Config>,
+    Fp2<<P as Bls12Config>::Fp2Config>,
+    Fp2<<P as Bls12Config>::Fp2Config>,
+);
+
+// The accumulator of non-fixed Q in the miller loop, with homogeneous projective coordinates.
+//
+// Ref: https://eprint.iacr.org/2013/722.pdf
+pub struct G2HomProjective<P: Bls12Config> {
+    pub x: Fp2<P::Fp2Config>,
+    pub y: Fp2<P::Fp2Config>,
+    pub z: Fp2<P::Fp2Config>,
+}
+
+impl<P: Bls12Config> G2HomProjective<P> {
+    pub fn new(q: &G2Affine<P>) -> Self {
+        let (&x, &y) = q.xy().unwrap();
+        Self { x, y, z: Fp2::ONE }
+    }
+
+    pub fn double_in_place(&mut self, two_inv: &P::Fp) -> EllCoeff<P> {
+        let mut a = self.x * self.y;
+        a.mul_assign_by_fp(two_inv);
+        let b = self.y.square();
+        let c = self.z.square();
+        let e = P::G2Config::COEFF_B * (c.double() + c);
+        let f = e.double() + e;
+        let mut g = b + f;
+        g.mul_assign_by_fp(two_inv);
+        let h = (self.y + self.z).square() - (b + c);
+        let i = e - b;
+        let j = self.x.square();
+        let e_square = e.square();
+
+        self.x = a * (b - f);
+        self.y = g.square() - (e_square.double() + e_square);
+        self.z = b * h;
+        match P::TWIST_TYPE {
+            TwistType::M => (i, j.double() + j, -h),
+            TwistType::D => (-h, j.double() + j, i),
+        }
+    }
+
+    pub fn add_in_place(&mut self, q: &G2Affine<P>) -> EllCoeff<P> {
+        let (&qx, &qy) = q.xy().unwrap();
+        let theta = self.y - (qy * self.z);
+        let lambda = self.x - (qx * self.z);
+        let c = theta.square();
+        let d = lambda.square();
+        let e = lambda * d;
+        let f = self.z * c;
+        let g = self.x * d;
+        let h = e + f - g.double();
+        self.x = lambda * h;
+        self.y = theta * (g - h) - (e * self.y);
+        self.z *= e;
+        let j = theta * qx - (lambda * qy);
+
+        match P::TWIST_TYPE {
+            TwistType::M => (j, -theta, lambda),
+            TwistType::D => (lambda, -theta, j),
+        }
+    }
+}
+
+// Evaluate the line function at point p, and accumulate it into f.
+//  M-type twist: sparse multiplication by_014
+//  D-type twist: sparse multiplication by_034
+pub fn ell<P: Bls12Config>(f: &mut Fp12<P::Fp12Config>, coeffs: &EllCoeff<P>, p: &G1Affine<P>) {
+    let (mut c0, mut c1, mut c2) = *coeffs;
+    let (px, py) = p.xy().unwrap();
+
+    match P::TWIST_TYPE {
+        TwistType::M => {
+            c2.mul_assign_by_fp(py);
+            c1.mul_assign_by_fp(px);
+            f.mul_by_014(&c0, &c1, &c2);
+        }
+        TwistType::D => {
+            c0.mul_assign_by_fp(py);
+            c1.mul_assign_by_fp(px);
+            f.mul_by_034(&c0, &c1, &c2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_ec::bls12::G2Prepared;
+    use ark_ff::BitIteratorBE;
+    use ark_std::UniformRand;
+
+    fn test_lines_with_g2_prepared_on<P: Bls12Config>() {
+        let rng = &mut ark_std::test_rng();
+        let q = G2Affine::<P>::rand(rng);
+        let two_inv = P::Fp::ONE.double().inverse().unwrap();
+
+        // the lines of the accumulator equal to the precomputed lines.
+        let mut T = G2HomProjective::<P>::new(&q);
+        let mut lines = vec![];
+        for bit in BitIteratorBE::without_leading_zeros(P::X).skip(1) {
+            lines.push(T.double_in_place(&two_inv));
+            if bit {
+                lines.push(T.add_in_place(&q));
+            }
+        }
+        assert_eq!(lines, G2Prepared::<P>::from(q).ell_coeffs);
+    }
+
+    #[test]
+    fn test_lines_with_g2_prepared() {
+        // M-type twist
+        test_lines_with_g2_prepared_on::<ark_bls12_381::Config>();
+        // D-type twist
+        test_lines_with_g2_prepared_on::<ark_bls12_377::Config>();
+    }
+}
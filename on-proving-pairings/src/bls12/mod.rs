@@ -0,0 +1,11 @@
+// Proving pairings over BLS12 curves.
+//
+// The optimal ate miller loop of BLS12 curves only runs over |x|, without the frobenius
+// correction lines of BN curves. As x ≡ p mod r, λ = p - x is a multiple of r, and
+//      c^lambda = f * wi <==> f * c^x * wi * c_inv^p = 1
+// where f is the miller loop over |x|, and c^x is folded into the miller loop.
+pub mod g2;
+pub mod params;
+pub mod prover;
+pub mod setup;
+pub mod verifier;
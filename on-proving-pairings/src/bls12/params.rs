@@ -0,0 +1,103 @@
+use crate::error::ParamsError;
+use crate::lambda_residues::ResidueParams;
+use ark_ec::bls12::Bls12Config;
+use ark_ec::CurveConfig;
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use once_cell::sync::Lazy;
+
+// BLS12 curves with the λ residues parameters.
+pub trait Bls12ResidueConfig: Bls12Config {
+    fn residue_params() -> &'static ResidueParams;
+}
+
+// λ = p - x, which is a multiple of r, since x ≡ p mod r.
+pub fn lambda<P: Bls12Config>() -> BigUint {
+    let p: BigUint = P::Fp::MODULUS.into();
+    let x = P::X
+        .iter()
+        .rev()
+        .fold(BigUint::from(0_u32), |acc, limb| (acc << 64) + limb);
+    if P::X_IS_NEGATIVE {
+        p + x
+    } else {
+        p - x
+    }
+}
+
+// Derive the λ residues parameters from p, r and x, with embedding degree 12.
+pub fn residue_params<P: Bls12Config>() -> Result<ResidueParams, ParamsError> {
+    let p: BigUint = P::Fp::MODULUS.into();
+    let r: BigUint = <P::G1Config as CurveConfig>::ScalarField::MODULUS.into();
+    ResidueParams::new(&p, &r, 12, lambda::<P>())
+}
+
+// BLS12-381: x is negative, and h_bad = 3^3 * 11 * 10177 * 859267 * 52437899.
+static BLS12_381: Lazy<ResidueParams> = Lazy::new(|| {
+    residue_params::<ark_bls12_381::Config>().expect("the parameters of BLS12-381 are valid")
+});
+
+impl Bls12ResidueConfig for ark_bls12_381::Config {
+    fn residue_params() -> &'static ResidueParams {
+        &BLS12_381
+    }
+}
+
+// BLS12-377: x is positive, and h_bad = 2^48 * 3^2 * 7 * 13 * 499.
+//
+// Unlike BN254 and BLS12-381, λ shares the high 2-adicity of p - 1 with h, and the 3-adicity
+// of h is 2 rather than 3. wi absorbs both the 2-part and 3-part of f, so no square roots
+// over the 2-adic tower or cube roots are needed.
+static BLS12_377: Lazy<ResidueParams> = Lazy::new(|| {
+    residue_params::<ark_bls12_377::Config>().expect("the parameters of BLS12-377 are valid")
+});
+
+impl Bls12ResidueConfig for ark_bls12_377::Config {
+    fn residue_params() -> &'static ResidueParams {
+        &BLS12_377
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_bls12_381_params() {
+        let params = ark_bls12_381::Config::residue_params();
+        let r: BigUint = ark_bls12_381::Fr::MODULUS.into();
+
+        // λ = p + |x|, since x is negative.
+        let p: BigUint = ark_bls12_381::Fq::MODULUS.into();
+        assert_eq!(params.lambda, p + 0xd201000000010000_u64);
+        assert_eq!(&params.lambda % &r, BigUint::from(0_u32));
+
+        // h_bad = 3^3 * 11 * 10177 * 859267 * 52437899
+        let h_bad = BigUint::from_str("136191386006474784777").unwrap();
+        assert_eq!(
+            h_bad,
+            BigUint::from(27_u32 * 11 * 10177) * 859267_u32 * 52437899_u32
+        );
+        assert_eq!(params.h_bad, h_bad);
+        assert_eq!(&params.h % &h_bad, BigUint::from(0_u32));
+    }
+
+    #[test]
+    fn test_bls12_377_params() {
+        let params = ark_bls12_377::Config::residue_params();
+        let r: BigUint = ark_bls12_377::Fr::MODULUS.into();
+
+        // λ = p - x, since x is positive.
+        let p: BigUint = ark_bls12_377::Fq::MODULUS.into();
+        assert_eq!(params.lambda, p - 0x8508c00000000001_u64);
+        assert_eq!(&params.lambda % &r, BigUint::from(0_u32));
+
+        // h_bad = 2^48 * 3^2 * 7 * 13 * 499
+        let h_bad = (BigUint::from(1_u32) << 48) * (9_u32 * 7 * 13 * 499);
+        assert_eq!(params.h_bad, h_bad);
+        // 2-adicity of λ and h
+        assert_eq!(params.lambda.trailing_zeros(), Some(92));
+        assert_eq!(params.h.trailing_zeros(), Some(48));
+    }
+}
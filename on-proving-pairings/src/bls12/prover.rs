@@ -0,0 +1,211 @@
+// Generate proof of pairings over BLS12 curves.
+use crate::bls12::g2::{ell, G2HomProjective};
+use crate::bls12::params::Bls12ResidueConfig;
+use crate::bls12::setup::PairingPVKey;
+use crate::error::PairingError;
+use crate::residue_proof::PairingProof;
+use ark_ec::bls12::{Bls12Config, G1Affine, G2Affine, G2Prepared};
+use ark_ec::AffineRepr;
+use ark_ff::fields::fp12_2over3over2::Fp12;
+use ark_ff::{BitIteratorBE, Field};
+use std::marker::PhantomData;
+
+// Prove pairing and generate proof: (c, wi).
+//
+// And can verify the proof by check:  c^lambda = f * wi, namely f * c^x * wi * c_inv^p = 1
+pub struct PairingProver<P: Bls12Config>(PhantomData<P>);
+
+impl<P: Bls12ResidueConfig> PairingProver<P> {
+    // Prove multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=1`.
+    // Will generate proof: (c, wi), satisfying c^lambda = f * wi
+    //
+    // params:
+    //  @pvk: precompute miller lines for fixed [Q1,...,Qn]
+    //  @eval_points: [P1,...,Pn]. which maps with fixed [Q1,...,Qn]
+    //  @non_fixed_points: [P'1,...,P'm]. which maps with non-fixed [Q'1,...,Q'm]
+    //  @non_fixed_q: [Q'1,...,Q'm]
+    pub fn prove(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+    ) -> Result<PairingProof<Fp12<P::Fp12Config>>, PairingError> {
+        // 1. compute f with the miller loop over |x|, namely the merged one without c.
+        let f = Self::merged_miller_loop(
+            &eval_points,
+            &pvk.lines,
+            &non_fixed_points,
+            &non_fixed_q,
+            Fp12::ONE,
+        )?;
+
+        // 2. finding_c
+        let (c, wi) = P::residue_params().finding_c(f)?;
+        let c_inv = c.inverse().ok_or(PairingError::InvalidWitness)?;
+
+        Ok(PairingProof {
+            c,
+            wi,
+            c_inv: Some(c_inv),
+        })
+    }
+
+    // Merged miller loop with c^x folded in, which is shared by the prover and the verifier.
+    //
+    // The pairs with zero Pi or Qi are skipped, as their miller loops are one.
+    //
+    // params:
+    //  @c_x: c for positive x, or c_inv for negative x.
+    //
+    // Return final_f = f * c^x, where f is the miller loop over |x|.
+    pub(crate) fn merged_miller_loop(
+        eval_points: &[G1Affine<P>],
+        lines: &[G2Prepared<P>],
+        non_fixed_points: &[G1Affine<P>],
+        non_fixed_q: &[G2Affine<P>],
+        c_x: Fp12<P::Fp12Config>,
+    ) -> Result<Fp12<P::Fp12Config>, PairingError> {
+        // Each fixed Pi should map with one precompute line, and each non-fixed Pi should map with one Qi
+        if eval_points.len() != lines.len() || non_fixed_points.len() != non_fixed_q.len() {
+            return Err(PairingError::WrongInputLength);
+        }
+
+        let mut fixed = eval_points
+            .iter()
+            .zip(lines)
+            .filter(|(p, line)| !p.is_zero() && !line.is_zero())
+            .map(|(p, line)| (p, line.ell_coeffs.iter()))
+            .collect::<Vec<_>>();
+        // Ti: the accumulators for non-fixed Qi
+        let mut non_fixed = non_fixed_points
+            .iter()
+            .zip(non_fixed_q)
+            .filter(|(p, q)| !p.is_zero() && !q.is_zero())
+            .map(|(p, q)| (p, q, G2HomProjective::<P>::new(q)))
+            .collect::<Vec<_>>();
+
+        // constants: 1/2
+        let two_inv = P::Fp::ONE.double().inverse().unwrap();
+
+        // 1. f = c^x, as the most significant bit of |x| is one.
+        let mut f = c_x;
+
+        // 2. miller loop part, |x|
+        for bit in BitIteratorBE::without_leading_zeros(P::X).skip(1) {
+            // 2.1 double: f = f * f
+            f.square_in_place();
+
+            // 2.2 mul c^x, if bit == 1
+            if bit {
+                f *= c_x;
+            }
+
+            // 2.3 accumulate double lines (fixed and non-fixed)
+            for (p, line) in fixed.iter_mut() {
+                let coeffs = line.next().ok_or(PairingError::WrongInputLength)?;
+                ell::<P>(&mut f, coeffs, p);
+            }
+            for (p, _, T) in non_fixed.iter_mut() {
+                let coeffs = T.double_in_place(&two_inv);
+                ell::<P>(&mut f, &coeffs, p);
+            }
+
+            if bit {
+                // 2.4 accumulate add lines (fixed and non-fixed)
+                for (p, line) in fixed.iter_mut() {
+                    let coeffs = line.next().ok_or(PairingError::WrongInputLength)?;
+                    ell::<P>(&mut f, coeffs, p);
+                }
+                for (p, q, T) in non_fixed.iter_mut() {
+                    let coeffs = T.add_in_place(q);
+                    ell::<P>(&mut f, &coeffs, p);
+                }
+            }
+        }
+
+        // return final_f
+        Ok(f)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::utils::sample_pairing_equation_on;
+    use ark_ec::bls12::Bls12;
+    use ark_ec::pairing::Pairing;
+    use ark_ff::CyclotomicMultSubgroup;
+    use ark_std::{UniformRand, Zero};
+
+    // the miller loop of arkworks, without the conjugation for negative x.
+    pub(crate) fn ark_miller_loop<P: Bls12Config>(
+        Ps: Vec<G1Affine<P>>,
+        Qs: Vec<G2Affine<P>>,
+    ) -> Fp12<P::Fp12Config> {
+        let mut f = Bls12::<P>::multi_miller_loop(Ps, Qs).0;
+        if P::X_IS_NEGATIVE {
+            f.cyclotomic_inverse_in_place();
+        }
+        f
+    }
+
+    fn test_miller_loop_on<P: Bls12ResidueConfig>() {
+        let rng = &mut ark_std::test_rng();
+        let (mut Ps, mut Qs) = sample_pairing_equation_on::<Bls12<P>, _>(4, rng);
+        // the pairs with zero point are skipped.
+        Ps.push(G1Affine::<P>::zero());
+        Qs.push(G2Affine::<P>::rand(rng));
+        Ps[1] = G1Affine::<P>::rand(rng);
+
+        let pvk = PairingPVKey::<P>::setup(Qs[0..2].to_vec());
+        let f = PairingProver::<P>::merged_miller_loop(
+            &Ps[0..2],
+            &pvk.lines,
+            &Ps[2..],
+            &Qs[2..],
+            Fp12::ONE,
+        )
+        .unwrap();
+        assert_eq!(f, ark_miller_loop::<P>(Ps, Qs));
+    }
+
+    #[test]
+    fn test_miller_loop() {
+        test_miller_loop_on::<ark_bls12_381::Config>();
+        test_miller_loop_on::<ark_bls12_377::Config>();
+    }
+
+    fn test_prove_multi_pairing_on<P: Bls12ResidueConfig>() {
+        let rng = &mut ark_std::test_rng();
+        let (Ps, Qs) = sample_pairing_equation_on::<Bls12<P>, _>(4, rng);
+        assert!(Bls12::<P>::multi_pairing(Ps.clone(), Qs.clone()).is_zero());
+
+        // Q1,Q2,Q3 are fixed, and Q4 is non-fixed.
+        let pvk = PairingPVKey::<P>::setup(Qs[0..3].to_vec());
+        let proof =
+            PairingProver::<P>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[3]], vec![Qs[3]]).unwrap();
+
+        // c^lambda = f * wi, where wi^{h_bad} = 1
+        let params = P::residue_params();
+        let f = ark_miller_loop::<P>(Ps.clone(), Qs.clone());
+        assert_eq!(proof.c.pow(params.lambda.to_u64_digits()), f * proof.wi);
+        assert!(params.check_wi(&proof.wi).is_ok());
+        assert_eq!(proof.c * proof.c_inv.unwrap(), Fp12::ONE);
+
+        // the pairing product is not 1
+        let res = PairingProver::<P>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[0]], vec![Qs[3]]);
+        assert_eq!(res, Err(PairingError::NotRthResidue));
+
+        // mismatched lengths
+        let res = PairingProver::<P>::prove(&pvk, Ps[0..2].to_vec(), vec![Ps[3]], vec![Qs[3]]);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+        let res = PairingProver::<P>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[3]], vec![]);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+    }
+
+    #[test]
+    fn test_prove_multi_pairing() {
+        test_prove_multi_pairing_on::<ark_bls12_381::Config>();
+        test_prove_multi_pairing_on::<ark_bls12_377::Config>();
+    }
+}
@@ -0,0 +1,19 @@
+use ark_ec::bls12::{Bls12Config, G2Prepared};
+
+// To prove and verify the pairing, need precompute the miller lines for the fixed Qi.
+//
+// The key is statement-independent, it can be reused by all the pairing proofs over the same fixed Qi.
+pub struct PairingPVKey<P: Bls12Config> {
+    // precompute miller lines for fixed Qi.
+    //  Base line: [Q1,...,Qn]
+    pub lines: Vec<G2Prepared<P>>,
+}
+
+impl<P: Bls12Config> PairingPVKey<P> {
+    pub fn setup(fixed_q: impl IntoIterator<Item = impl Into<G2Prepared<P>>>) -> Self {
+        // precompute lines for fixed Qi
+        let lines = fixed_q.into_iter().map(|q| q.into()).collect();
+
+        Self { lines }
+    }
+}
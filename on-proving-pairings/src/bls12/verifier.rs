@@ -0,0 +1,117 @@
+use crate::bls12::params::Bls12ResidueConfig;
+use crate::bls12::prover::PairingProver;
+use crate::bls12::setup::PairingPVKey;
+use crate::error::PairingError;
+use crate::residue_proof::PairingProof;
+use ark_ec::bls12::{Bls12Config, G1Affine, G2Affine};
+use ark_ff::fields::fp12_2over3over2::Fp12;
+use ark_ff::Field;
+use std::marker::PhantomData;
+
+pub struct PairingVerifier<P: Bls12Config>(PhantomData<P>);
+
+impl<P: Bls12ResidueConfig> PairingVerifier<P> {
+    // Verify multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=1` with the pairing proof.
+    //
+    // Only public data and the witness (c, wi) are used:
+    //  1. redo the merged miller loop with c^x folded in, namely final_f = f * c^x
+    //  2. apply the frobenius correction: final_f * wi * c_inv^p
+    //  3. check the result equals one, namely c_inv^lambda * f * wi = 1
+    //
+    // params:
+    //  @pvk: precompute miller lines for fixed [Q1,...,Qn]
+    //  @eval_points: [P1,...,Pn]. which maps with fixed [Q1,...,Qn]
+    //  @non_fixed_points: [P'1,...,P'm]. which maps with non-fixed [Q'1,...,Q'm]
+    //  @non_fixed_q: [Q'1,...,Q'm]
+    //  @proof: pairing proof (c, wi), satisfying c^lambda = f*wi
+    //
+    // return: Ok(false) if the check fails, or the error if the inputs or proof are malformed.
+    pub fn verify(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        proof: &PairingProof<Fp12<P::Fp12Config>>,
+    ) -> Result<bool, PairingError> {
+        // check the hint c_inv if provided, otherwise compute it.
+        let c_inv = match proof.c_inv {
+            Some(c_inv) if proof.c * c_inv == Fp12::ONE => c_inv,
+            Some(_) => return Err(PairingError::InvalidWitness),
+            None => proof.c.inverse().ok_or(PairingError::InvalidWitness)?,
+        };
+        // reject the proof if wi^{h_bad} != 1.
+        P::residue_params().check_wi(&proof.wi)?;
+
+        // 1. final_f = f * c^x
+        let c_x = if P::X_IS_NEGATIVE { c_inv } else { proof.c };
+        let final_f = PairingProver::<P>::merged_miller_loop(
+            &eval_points,
+            &pvk.lines,
+            &non_fixed_points,
+            &non_fixed_q,
+            c_x,
+        )?;
+
+        // 2. final_f * wi * c_inv^p
+        let res = final_f * proof.wi * c_inv.frobenius_map(1);
+
+        // 3. c_inv^lambda * f * wi = 1
+        Ok(res == Fp12::ONE)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bls12::prover::test::ark_miller_loop;
+    use crate::residue_proof::test::{check_forged_wi, check_verify};
+    use crate::utils::sample_pairing_equation_on;
+    use ark_ec::bls12::Bls12;
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    fn test_verify_on<P: Bls12ResidueConfig>() {
+        let rng = &mut ark_std::test_rng();
+        let (Ps, Qs) = sample_pairing_equation_on::<Bls12<P>, _>(4, rng);
+        let pvk = PairingPVKey::<P>::setup(Qs[0..3].to_vec());
+        let proof =
+            PairingProver::<P>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[3]], vec![Qs[3]]).unwrap();
+        let verify = |P4: G1Affine<P>, proof: &PairingProof<Fp12<P::Fp12Config>>| {
+            PairingVerifier::<P>::verify(&pvk, Ps[0..3].to_vec(), vec![P4], vec![Qs[3]], proof)
+        };
+        let P4 = (Ps[3] + G1Affine::<P>::generator()).into_affine();
+        check_verify(verify, &proof, Ps[3], P4);
+
+        // wrong length
+        let res = PairingVerifier::<P>::verify(&pvk, Ps[0..2].to_vec(), vec![], vec![], &proof);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+    }
+
+    #[test]
+    fn test_verify() {
+        test_verify_on::<ark_bls12_381::Config>();
+        test_verify_on::<ark_bls12_377::Config>();
+    }
+
+    fn test_verify_with_forged_wi_on<P: Bls12ResidueConfig>() {
+        let rng = &mut ark_std::test_rng();
+        let (Ps, Qs) = sample_pairing_equation_on::<Bls12<P>, _>(4, rng);
+        let pvk = PairingPVKey::<P>::setup(Qs[0..3].to_vec());
+        let proof =
+            PairingProver::<P>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[3]], vec![Qs[3]]).unwrap();
+        let verify = |P4: G1Affine<P>, proof: &PairingProof<Fp12<P::Fp12Config>>| {
+            PairingVerifier::<P>::verify(&pvk, Ps[0..3].to_vec(), vec![P4], vec![Qs[3]], proof)
+        };
+
+        let P4 = (Ps[3] + G1Affine::<P>::generator()).into_affine();
+        let wrong_f =
+            ark_miller_loop::<P>(Ps[0..3].iter().copied().chain([P4]).collect(), Qs.clone());
+        let lambda = P::residue_params().lambda.to_u64_digits();
+        check_forged_wi(verify, &proof, (Ps[3], P4), wrong_f, &lambda, rng);
+    }
+
+    #[test]
+    fn test_verify_with_forged_wi() {
+        test_verify_with_forged_wi_on::<ark_bls12_381::Config>();
+        test_verify_with_forged_wi_on::<ark_bls12_377::Config>();
+    }
+}
@@ -0,0 +1,390 @@
+// BN-P254 with x = -(2^62 + 2^55 + 1), for testing the pipeline over a BN curve of negative x.
+//
+//  p = 36x^4 + 36x^3 + 24x^2 + 6x + 1
+//  r = 36x^4 + 36x^3 + 18x^2 + 6x + 1
+//
+// Unlike BN254 (h_bad = 27, d = 3), h_bad = 3^4 * 5 and d = gcd(λ, h_bad) = 15.
+// The miller loop runs over |6x + 2|, then conjugates f, see `PairingProver::merged_miller_loop`.
+//
+// E: y^2 = x^3 + 2 over Fp, and the D-type twist E': y^2 = x^3 + 2/ξ over Fp2, where ξ = 1 + u.
+// Ref: Beuchat et al., High-Speed Software Implementation of the Optimal Ate Pairing over Barreto-Naehrig Curves
+
+// `MontConfig` of ark_ff_macros 0.4 expands to an impl inside a const item,
+// and checks the `asm` feature of ark-ff for 4 limbs.
+#![allow(non_local_definitions, unexpected_cfgs)]
+use crate::lambda_residues::BnResidueParams;
+use crate::params::BnResidueConfig;
+use ark_ec::bn::{Bn, BnConfig, TwistType};
+use ark_ec::models::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::models::CurveConfig;
+use ark_ff::fields::{
+    Fp12, Fp12Config, Fp2, Fp256, Fp2Config, Fp6, Fp6Config, MontBackend, MontConfig,
+};
+use ark_ff::{Field, MontFp, Zero};
+use once_cell::sync::Lazy;
+
+#[derive(MontConfig)]
+#[modulus = "16798108731015832284940804142231733909889187121439069848933715426072753864723"]
+#[generator = "3"]
+pub struct FqConfig;
+pub type Fq = Fp256<MontBackend<FqConfig, 4>>;
+
+#[derive(MontConfig)]
+#[modulus = "16798108731015832284940804142231733909759579603404752749028378864165570215949"]
+#[generator = "2"]
+pub struct FrConfig;
+pub type Fr = Fp256<MontBackend<FrConfig, 4>>;
+
+pub type Fq2 = Fp2<Fq2Config>;
+pub struct Fq2Config;
+
+impl Fp2Config for Fq2Config {
+    type Fp = Fq;
+
+    // u^2 = -1, as p = 3 mod 4
+    const NONRESIDUE: Fq = MontFp!("-1");
+
+    const FROBENIUS_COEFF_FP2_C1: &'static [Fq] = &[Fq::ONE, MontFp!("-1")];
+}
+
+pub type Fq6 = Fp6<Fq6Config>;
+#[derive(Clone, Copy)]
+pub struct Fq6Config;
+
+impl Fp6Config for Fq6Config {
+    type Fp2Config = Fq2Config;
+
+    // ξ = 1 + u, which is neither square nor cube in Fp2
+    const NONRESIDUE: Fq2 = Fq2::new(Fq::ONE, Fq::ONE);
+
+    // ξ^{(p^i - 1) / 3}
+    const FROBENIUS_COEFF_FP6_C1: &'static [Fq2] = &[
+        Fq2::new(Fq::ONE, Fq::ZERO),
+        Fq2::new(
+            Fq::ZERO,
+            MontFp!(
+                "16798108731015832283133667796947756444075910019074449559301910896669540483083"
+            ),
+        ),
+        Fq2::new(
+            MontFp!("1807136345283977465813277102364620289631804529403213381639"),
+            Fq::ZERO,
+        ),
+        Fq2::new(Fq::ZERO, Fq::ONE),
+        Fq2::new(
+            MontFp!(
+                "16798108731015832283133667796947756444075910019074449559301910896669540483083"
+            ),
+            Fq::ZERO,
+        ),
+        Fq2::new(
+            Fq::ZERO,
+            MontFp!("1807136345283977465813277102364620289631804529403213381639"),
+        ),
+    ];
+
+    // ξ^{2 * (p^i - 1) / 3}
+    const FROBENIUS_COEFF_FP6_C2: &'static [Fq2] = &[
+        Fq2::new(Fq::ONE, Fq::ZERO),
+        Fq2::new(
+            MontFp!(
+                "16798108731015832283133667796947756444075910019074449559301910896669540483084"
+            ),
+            Fq::ZERO,
+        ),
+        Fq2::new(
+            MontFp!(
+                "16798108731015832283133667796947756444075910019074449559301910896669540483083"
+            ),
+            Fq::ZERO,
+        ),
+        Fq2::new(MontFp!("-1"), Fq::ZERO),
+        Fq2::new(
+            MontFp!("1807136345283977465813277102364620289631804529403213381639"),
+            Fq::ZERO,
+        ),
+        Fq2::new(
+            MontFp!("1807136345283977465813277102364620289631804529403213381640"),
+            Fq::ZERO,
+        ),
+    ];
+}
+
+pub type Fq12 = Fp12<Fq12Config>;
+#[derive(Clone, Copy)]
+pub struct Fq12Config;
+
+impl Fp12Config for Fq12Config {
+    type Fp6Config = Fq6Config;
+
+    const NONRESIDUE: Fq6 = Fq6::new(Fq2::ZERO, Fq2::ONE, Fq2::ZERO);
+
+    // ξ^{(p^i - 1) / 6}
+    const FROBENIUS_COEFF_FP12_C1: &'static [Fq2] = &[
+        Fq2::new(Fq::ONE, Fq::ZERO),
+        Fq2::new(
+            MontFp!(
+                "12310438583873020660552735091161044116898065562217439662059245424880585960937"
+            ),
+            MontFp!("4487670147142811624388069051070689792991121559221630186874470001192167903786"),
+        ),
+        Fq2::new(
+            MontFp!("1807136345283977465813277102364620289631804529403213381640"),
+            Fq::ZERO,
+        ),
+        Fq2::new(
+            MontFp!("571759232279933406358082416437452803737039382138144404732186496954757578318"),
+            MontFp!(
+                "16226349498735898878582721725794281106152147739300925444201528929117996286405"
+            ),
+        ),
+        Fq2::new(
+            MontFp!("1807136345283977465813277102364620289631804529403213381639"),
+            Fq::ZERO,
+        ),
+        Fq2::new(
+            MontFp!("5059429379422745030746151467508142596728160941359774591606656498146925482104"),
+            MontFp!(
+                "11738679351593087254194652674723591313161026180079295257327058927925828382619"
+            ),
+        ),
+        Fq2::new(MontFp!("-1"), Fq::ZERO),
+        Fq2::new(
+            MontFp!("4487670147142811624388069051070689792991121559221630186874470001192167903786"),
+            MontFp!(
+                "12310438583873020660552735091161044116898065562217439662059245424880585960937"
+            ),
+        ),
+        Fq2::new(
+            MontFp!(
+                "16798108731015832283133667796947756444075910019074449559301910896669540483083"
+            ),
+            Fq::ZERO,
+        ),
+        Fq2::new(
+            MontFp!(
+                "16226349498735898878582721725794281106152147739300925444201528929117996286405"
+            ),
+            MontFp!("571759232279933406358082416437452803737039382138144404732186496954757578318"),
+        ),
+        Fq2::new(
+            MontFp!(
+                "16798108731015832283133667796947756444075910019074449559301910896669540483084"
+            ),
+            Fq::ZERO,
+        ),
+        Fq2::new(
+            MontFp!(
+                "11738679351593087254194652674723591313161026180079295257327058927925828382619"
+            ),
+            MontFp!("5059429379422745030746151467508142596728160941359774591606656498146925482104"),
+        ),
+    ];
+}
+
+pub struct G1Config;
+
+impl CurveConfig for G1Config {
+    type BaseField = Fq;
+    type ScalarField = Fr;
+
+    // #E(Fp) = p + 1 - (6x^2 + 1) = r
+    const COFACTOR: &'static [u64] = &[1];
+    const COFACTOR_INV: Fr = Fr::ONE;
+}
+
+impl SWCurveConfig for G1Config {
+    const COEFF_A: Fq = Fq::ZERO;
+    const COEFF_B: Fq = MontFp!("2");
+    const GENERATOR: Affine<Self> = Affine::new_unchecked(MontFp!("-1"), Fq::ONE);
+
+    fn mul_by_a(_: Self::BaseField) -> Self::BaseField {
+        Self::BaseField::zero()
+    }
+}
+
+pub struct G2Config;
+
+impl CurveConfig for G2Config {
+    type BaseField = Fq2;
+    type ScalarField = Fr;
+
+    // #E'(Fp2) = r * (2p - r)
+    const COFACTOR: &'static [u64] = &[
+        12465963768561532953,
+        14024912927073501206,
+        13417434401994702856,
+        2676093114170474497,
+    ];
+    const COFACTOR_INV: Fr =
+        MontFp!("8399054365507916140663265725831889489001708940320597534943659766172318957567");
+}
+
+impl SWCurveConfig for G2Config {
+    const COEFF_A: Fq2 = Fq2::ZERO;
+    // 2 / ξ = 1 - u
+    const COEFF_B: Fq2 = Fq2::new(Fq::ONE, MontFp!("-1"));
+    const GENERATOR: Affine<Self> = Affine::new_unchecked(
+        Fq2::new(
+            MontFp!("3183461328059746161485255759853561254315005693971655640515975790101400121822"),
+            MontFp!(
+                "16341354648347783937658568662356235557911266819982186341504448800805826672123"
+            ),
+        ),
+        Fq2::new(
+            MontFp!(
+                "11566804073510324167168541376218210073234268823825329937730434569959760772230"
+            ),
+            MontFp!(
+                "12909998676506940572682975523826511778201501699493495576689004888713184096183"
+            ),
+        ),
+    );
+
+    fn mul_by_a(_: Self::BaseField) -> Self::BaseField {
+        Self::BaseField::zero()
+    }
+}
+
+pub struct Config;
+
+impl BnConfig for Config {
+    // |x| = 2^62 + 2^55 + 1
+    const X: &'static [u64] = &[4647714815446351873];
+    const X_IS_NEGATIVE: bool = true;
+    // |6x + 2| = 2^64 + 2^63 + 2^57 + 2^56 + 2^2
+    const ATE_LOOP_COUNT: &'static [i8] = &[
+        0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0,
+        0, 0, 0, 1, 1,
+    ];
+
+    // ξ^{(p - 1) / 3} and ξ^{(p - 1) / 2}
+    const TWIST_MUL_BY_Q_X: Fq2 = Fq2::new(
+        Fq::ZERO,
+        MontFp!("16798108731015832283133667796947756444075910019074449559301910896669540483083"),
+    );
+    const TWIST_MUL_BY_Q_Y: Fq2 = Fq2::new(
+        MontFp!("16226349498735898878582721725794281106152147739300925444201528929117996286405"),
+        MontFp!("16226349498735898878582721725794281106152147739300925444201528929117996286405"),
+    );
+    const TWIST_TYPE: TwistType = TwistType::D;
+    type Fp = Fq;
+    type Fp2Config = Fq2Config;
+    type Fp6Config = Fq6Config;
+    type Fp12Config = Fq12Config;
+    type G1Config = G1Config;
+    type G2Config = G2Config;
+}
+
+pub type BnP254 = Bn<Config>;
+
+static BN_P254: Lazy<BnResidueParams<Config>> = Lazy::new(|| BnResidueParams::new().unwrap());
+
+impl BnResidueConfig for Config {
+    fn residue_params() -> &'static BnResidueParams<Self> {
+        &BN_P254
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lambda_residues::test::{test_finding_c_for_each_wi_on, test_residue_params_on};
+    use crate::prover::PairingProver;
+    use crate::setup::{PairingPVKey, PairingTarget};
+    use crate::utils::sample_pairing_equation_on;
+    use crate::verifier::test::test_prove_and_verify_on;
+    use crate::verifier::PairingVerifier;
+    use ark_ec::pairing::Pairing;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::PrimeField;
+    use ark_std::UniformRand;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_bn_p254() {
+        let rng = &mut ark_std::test_rng();
+
+        // the generators are in the r-torsion
+        let r: BigUint = Fr::MODULUS.into();
+        let g1 = Affine::<G1Config>::generator();
+        let g2 = Affine::<G2Config>::generator();
+        assert!(g1.is_on_curve() && g2.is_on_curve());
+        assert!(g1.mul_bigint(r.to_u64_digits()).is_zero());
+        assert!(g2.mul_bigint(r.to_u64_digits()).is_zero());
+        assert!(Affine::<G2Config>::rand(rng).is_in_correct_subgroup_assuming_on_curve());
+
+        // the frobenius map is raising to p
+        let f = Fq12::rand(rng);
+        assert_eq!(f.frobenius_map(1), f.pow(Fq::MODULUS));
+
+        // the pairing is bilinear and non-degenerate
+        let (P, Q) = sample_pairing_equation_on::<BnP254, _>(3, rng);
+        assert!(BnP254::multi_pairing(P, Q).is_zero());
+        assert!(!BnP254::pairing(g1, g2).is_zero());
+        let a = Fr::rand(rng);
+        assert_eq!(
+            BnP254::pairing((g1 * a).into_affine(), g2),
+            BnP254::pairing(g1, (g2 * a).into_affine())
+        );
+    }
+
+    #[test]
+    fn test_residue_params() {
+        test_residue_params_on::<Config>(405, 15);
+
+        // λ = 6x + 2 + p - p^2 + p^3, where 6x + 2 = -(6|x| - 2)
+        let p: BigUint = Fq::MODULUS.into();
+        let e = BigUint::from(Config::X[0]) * 6_u32 - 2_u32;
+        assert_eq!(
+            Config::residue_params().lambda,
+            &p + p.pow(3) - p.pow(2) - e
+        );
+    }
+
+    #[test]
+    fn test_finding_c_for_each_wi() {
+        test_finding_c_for_each_wi_on::<Config>(15);
+    }
+
+    #[test]
+    fn test_prove_and_verify() {
+        test_prove_and_verify_on::<Config>(15);
+    }
+
+    #[test]
+    fn test_prove_and_verify_with_target() {
+        let rng = &mut ark_std::test_rng();
+        let (P, Q) = sample_pairing_equation_on::<BnP254, _>(3, rng);
+
+        // e(P1,Q1) * e(P2,Q2) = T, where T = e(-P3,Q3)
+        let T = BnP254::pairing(-P[2], Q[2]);
+        let target = PairingTarget::new(T).unwrap();
+        let pvk = PairingPVKey::<Config>::setup(vec![Q[0]]);
+        let proof =
+            PairingProver::prove_with_target(&pvk, vec![P[0]], vec![P[1]], vec![Q[1]], &target)
+                .unwrap();
+        assert!(PairingVerifier::verify_with_target(
+            &pvk,
+            vec![P[0]],
+            vec![P[1]],
+            vec![Q[1]],
+            &target,
+            &proof
+        )
+        .unwrap());
+
+        // the wrong target, e.g. T^2
+        let wrong = PairingTarget::new(T + T).unwrap();
+        assert!(!PairingVerifier::verify_with_target(
+            &pvk,
+            vec![P[0]],
+            vec![P[1]],
+            vec![Q[1]],
+            &wrong,
+            &proof
+        )
+        .unwrap());
+    }
+}
@@ -0,0 +1,139 @@
+use ark_ec::bw6::{BW6Config, G1Affine, G2Affine, TwistType};
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ec::AffineRepr;
+use ark_ff::fields::fp6_2over3::Fp6;
+use ark_ff::Field;
+
+// Coefficients of the line evaluations, which are the same as `G2Prepared::ell_coeffs_1/2`.
+//
+// Unlike BLS12, G2 of BW6 is defined over Fp, so are the coefficients.
+pub type EllCoeff<P> = (
+    <P as BW6Config>::Fp,
+    <P as BW6Config>::Fp,
+    <P as BW6Config>::Fp,
+);
+
+// The accumulator of non-fixed Q in the miller loop, with homogeneous projective coordinates.
+//
+// Ref: https://eprint.iacr.org/2013/722.pdf
+pub struct G2HomProjective<P: BW6Config> {
+    pub x: P::Fp,
+    pub y: P::Fp,
+    pub z: P::Fp,
+}
+
+impl<P: BW6Config> G2HomProjective<P> {
+    pub fn new(q: &G2Affine<P>) -> Self {
+        let (&x, &y) = q.xy().unwrap();
+        Self {
+            x,
+            y,
+            z: P::Fp::ONE,
+        }
+    }
+
+    // Same as the one of arkworks, which scales the line by 4 rather than multiplying 1/2.
+    pub fn double_in_place(&mut self) -> EllCoeff<P> {
+        let a = self.x * self.y;
+        let b = self.y.square();
+        let b4 = b.double().double();
+        let c = self.z.square();
+        let e = P::G2Config::COEFF_B * (c.double() + c);
+        let f = e.double() + e;
+        let g = b + f;
+        let h = (self.y + self.z).square() - (b + c);
+        let i = e - b;
+        let j = self.x.square();
+        let e2_square = e.double().square();
+
+        self.x = a.double() * (b - f);
+        self.y = g.square() - (e2_square.double() + e2_square);
+        self.z = b4 * h;
+        match P::TWIST_TYPE {
+            TwistType::M => (i, j.double() + j, -h),
+            TwistType::D => (-h, j.double() + j, i),
+        }
+    }
+
+    pub fn add_in_place(&mut self, q: &G2Affine<P>) -> EllCoeff<P> {
+        let (&qx, &qy) = q.xy().unwrap();
+        let theta = self.y - (qy * self.z);
+        let lambda = self.x - (qx * self.z);
+        let c = theta.square();
+        let d = lambda.square();
+        let e = lambda * d;
+        let f = self.z * c;
+        let g = self.x * d;
+        let h = e + f - g.double();
+        self.x = lambda * h;
+        self.y = theta * (g - h) - (e * self.y);
+        self.z *= e;
+        let j = theta * qx - (lambda * qy);
+
+        match P::TWIST_TYPE {
+            TwistType::M => (j, -theta, lambda),
+            TwistType::D => (lambda, -theta, j),
+        }
+    }
+}
+
+// Evaluate the line function at point p, and accumulate it into f.
+//  M-type twist: sparse multiplication by_014
+//  D-type twist: sparse multiplication by_034
+pub fn ell<P: BW6Config>(f: &mut Fp6<P::Fp6Config>, coeffs: &EllCoeff<P>, p: &G1Affine<P>) {
+    let (mut c0, mut c1, mut c2) = *coeffs;
+    let (px, py) = p.xy().unwrap();
+
+    match P::TWIST_TYPE {
+        TwistType::M => {
+            c2 *= py;
+            c1 *= px;
+            f.mul_by_014(&c0, &c1, &c2);
+        }
+        TwistType::D => {
+            c0 *= py;
+            c1 *= px;
+            f.mul_by_034(&c0, &c1, &c2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_bw6_761::Config;
+    use ark_ec::bw6::G2Prepared;
+    use ark_ff::BitIteratorBE;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_lines_with_g2_prepared() {
+        let rng = &mut ark_std::test_rng();
+        let q = G2Affine::<Config>::rand(rng);
+        let expect = G2Prepared::<Config>::from(q);
+
+        // 1. the first part: u+1
+        let mut T = G2HomProjective::<Config>::new(&q);
+        let mut lines = vec![];
+        for bit in BitIteratorBE::without_leading_zeros(Config::ATE_LOOP_COUNT_1).skip(1) {
+            lines.push(T.double_in_place());
+            if bit {
+                lines.push(T.add_in_place(&q));
+            }
+        }
+        assert_eq!(lines, expect.ell_coeffs_1);
+
+        // 2. the second part: u^3-u^2-u in NAF
+        let mut T = G2HomProjective::<Config>::new(&q);
+        let mut lines = vec![];
+        for bit in Config::ATE_LOOP_COUNT_2.iter().rev().skip(1) {
+            lines.push(T.double_in_place());
+            match bit {
+                1 => lines.push(T.add_in_place(&q)),
+                -1 => lines.push(T.add_in_place(&-q)),
+                _ => {}
+            }
+        }
+        assert_eq!(lines, expect.ell_coeffs_2);
+    }
+}
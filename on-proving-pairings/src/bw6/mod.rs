@@ -0,0 +1,13 @@
+// Proving pairings over BW6 curves, e.g. BW6-761, the outer curve of BLS12-377 recursion.
+//
+// The optimal ate miller loop of BW6 curves has two parts:
+//      f = f_{u+1,Q}(P) * f_{u^3-u^2-u,Q}(P)^p
+// which is an r-th power residue when the pairing is one, with
+//      λ = (u+1) + p * (u^3-u^2-u)
+// As c^λ = c^{u+1} * (c^{u^3-u^2-u})^p, c_inv is folded into both parts of the miller loop, and
+//      c^lambda = f * wi <==> (f_1 * c_inv^{u+1}) * (f_2 * c_inv^{u^3-u^2-u})^p * wi = 1
+pub mod g2;
+pub mod params;
+pub mod prover;
+pub mod setup;
+pub mod verifier;
@@ -0,0 +1,100 @@
+use crate::error::ParamsError;
+use crate::lambda_residues::ResidueParams;
+use ark_ec::bw6::BW6Config;
+use ark_ec::CurveConfig;
+use ark_ff::PrimeField;
+use num_bigint::{BigInt, BigUint};
+use once_cell::sync::Lazy;
+
+// BW6 curves with the λ residues parameters.
+pub trait BW6ResidueConfig: BW6Config {
+    fn residue_params() -> &'static ResidueParams;
+}
+
+// λ = (u+1) + p * (u^3-u^2-u), namely ATE_LOOP_COUNT_1 + p * ATE_LOOP_COUNT_2.
+//
+// Only positive loop counts are supported, as c can't be folded into a conjugated part.
+pub fn lambda<P: BW6Config>() -> Result<BigUint, ParamsError> {
+    if P::ATE_LOOP_COUNT_1_IS_NEGATIVE || P::ATE_LOOP_COUNT_2_IS_NEGATIVE {
+        return Err(ParamsError::Condition("the ate loop counts are positive"));
+    }
+    let p: BigUint = P::Fp::MODULUS.into();
+    let count_1 = P::ATE_LOOP_COUNT_1
+        .iter()
+        .rev()
+        .fold(BigUint::from(0_u32), |acc, limb| (acc << 64) + limb);
+    // ATE_LOOP_COUNT_2 is in NAF, with the least significant digit first.
+    let count_2 = P::ATE_LOOP_COUNT_2
+        .iter()
+        .rev()
+        .fold(BigInt::from(0_i32), |acc, &digit| (acc << 1) + digit);
+
+    let count_2 = count_2
+        .to_biguint()
+        .ok_or(ParamsError::Condition("the ate loop counts are positive"))?;
+    Ok(count_1 + p * count_2)
+}
+
+// Derive the λ residues parameters from p, r and λ, with embedding degree 6.
+pub fn residue_params<P: BW6Config>() -> Result<ResidueParams, ParamsError> {
+    let p: BigUint = P::Fp::MODULUS.into();
+    let r: BigUint = <P::G1Config as CurveConfig>::ScalarField::MODULUS.into();
+    ResidueParams::new(&p, &r, 6, lambda::<P>()?)
+}
+
+// BW6-761: u is the x of BLS12-377, and r is the base field modulus of BLS12-377.
+//
+// gcd(λ, h) = 1 where h = (p^6 - 1)/r, so h_bad = 1 and d = 1: every f in the r-th residues
+// has the λ-th root c = f^{λ^{-1} mod h}, and wi is always one.
+static BW6_761: Lazy<ResidueParams> = Lazy::new(|| {
+    residue_params::<ark_bw6_761::Config>().expect("the parameters of BW6-761 are valid")
+});
+
+impl BW6ResidueConfig for ark_bw6_761::Config {
+    fn residue_params() -> &'static ResidueParams {
+        &BW6_761
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_ff::{Field, UniformRand};
+
+    #[test]
+    fn test_bw6_761_params() {
+        let params = ark_bw6_761::Config::residue_params();
+        let p: BigUint = ark_bw6_761::Fq::MODULUS.into();
+        let r: BigUint = ark_bw6_761::Fr::MODULUS.into();
+        // r is the base field modulus of BLS12-377
+        assert_eq!(r, ark_bls12_377::Fq::MODULUS.into());
+
+        // λ = (u+1) + p * (u^3-u^2-u), which is a multiple of r.
+        let u = BigUint::from(0x8508c00000000001_u64);
+        let count_2 = u.pow(3) - u.pow(2) - &u;
+        assert_eq!(params.lambda, (&u + 1_u32) + &p * count_2);
+        assert_eq!(&params.lambda % &r, BigUint::from(0_u32));
+
+        // h = (p^6 - 1)/r, and gcd(λ, h) = 1
+        assert_eq!(&params.h * &r, p.pow(6) - 1_u32);
+        assert_eq!(params.h_bad, BigUint::from(1_u32));
+    }
+
+    #[test]
+    fn test_finding_c() {
+        let rng = &mut ark_std::test_rng();
+        let params = ark_bw6_761::Config::residue_params();
+
+        // f = c^lambda is an r-th residue, and the root is found with wi = 1.
+        let c = ark_bw6_761::Fq6::rand(rng);
+        let f = c.pow(params.lambda.to_u64_digits());
+        let (c, wi) = params.finding_c(f).unwrap();
+        assert_eq!(wi, ark_bw6_761::Fq6::ONE);
+        assert_eq!(c.pow(params.lambda.to_u64_digits()), f);
+        assert!(params.check_wi(&wi).is_ok());
+
+        // a random f is not an r-th residue
+        let f = ark_bw6_761::Fq6::rand(rng);
+        assert!(params.finding_c(f).is_err());
+    }
+}
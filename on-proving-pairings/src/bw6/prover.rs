@@ -0,0 +1,237 @@
+// Generate proof of pairings over BW6 curves.
+use crate::bw6::g2::{ell, G2HomProjective};
+use crate::bw6::params::BW6ResidueConfig;
+use crate::bw6::setup::PairingPVKey;
+use crate::error::PairingError;
+use crate::residue_proof::PairingProof;
+use ark_ec::bw6::{BW6Config, G1Affine, G2Affine, G2Prepared};
+use ark_ec::AffineRepr;
+use ark_ff::fields::fp6_2over3::Fp6;
+use ark_ff::{BitIteratorBE, Field};
+use std::marker::PhantomData;
+
+// Prove pairing and generate proof: (c, wi).
+//
+// And can verify the proof by check:  c^lambda = f * wi,
+// namely (f_1 * c_inv^{u+1}) * (f_2 * c_inv^{u^3-u^2-u})^p * wi = 1
+pub struct PairingProver<P: BW6Config>(PhantomData<P>);
+
+impl<P: BW6ResidueConfig> PairingProver<P> {
+    // Prove multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=1`.
+    // Will generate proof: (c, wi), satisfying c^lambda = f * wi
+    //
+    // params:
+    //  @pvk: precompute miller lines for fixed [Q1,...,Qn]
+    //  @eval_points: [P1,...,Pn]. which maps with fixed [Q1,...,Qn]
+    //  @non_fixed_points: [P'1,...,P'm]. which maps with non-fixed [Q'1,...,Q'm]
+    //  @non_fixed_q: [Q'1,...,Q'm]
+    pub fn prove(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+    ) -> Result<PairingProof<Fp6<P::Fp6Config>>, PairingError> {
+        // 1. compute f with the two-part miller loop, namely the merged one without c.
+        let f = Self::merged_miller_loop(
+            &eval_points,
+            &pvk.lines,
+            &non_fixed_points,
+            &non_fixed_q,
+            (Fp6::ONE, Fp6::ONE),
+        )?;
+
+        // 2. finding_c
+        let (c, wi) = P::residue_params().finding_c(f)?;
+        let c_inv = c.inverse().ok_or(PairingError::InvalidWitness)?;
+
+        Ok(PairingProof {
+            c,
+            wi,
+            c_inv: Some(c_inv),
+        })
+    }
+
+    // Merged two-part miller loop with c_inv folded in, which is shared by the prover and the verifier.
+    //
+    // The pairs with zero Pi or Qi are skipped, as their miller loops are one.
+    //
+    // params:
+    //  @c: (c, c_inv), where c_inv is folded in for the digit 1 and c for the digit -1.
+    //
+    // Return final_f = (f_1 * c_inv^{u+1}) * (f_2 * c_inv^{u^3-u^2-u})^p.
+    pub(crate) fn merged_miller_loop(
+        eval_points: &[G1Affine<P>],
+        lines: &[G2Prepared<P>],
+        non_fixed_points: &[G1Affine<P>],
+        non_fixed_q: &[G2Affine<P>],
+        (c, c_inv): (Fp6<P::Fp6Config>, Fp6<P::Fp6Config>),
+    ) -> Result<Fp6<P::Fp6Config>, PairingError> {
+        // Each fixed Pi should map with one precompute line, and each non-fixed Pi should map with one Qi
+        if eval_points.len() != lines.len() || non_fixed_points.len() != non_fixed_q.len() {
+            return Err(PairingError::WrongInputLength);
+        }
+
+        let fixed = eval_points
+            .iter()
+            .zip(lines)
+            .filter(|(p, line)| !p.is_zero() && !line.is_zero())
+            .collect::<Vec<_>>();
+        let non_fixed = non_fixed_points
+            .iter()
+            .zip(non_fixed_q)
+            .filter(|(p, q)| !p.is_zero() && !q.is_zero())
+            .collect::<Vec<_>>();
+
+        // 1. the first part: u+1
+        let mut fixed_1 = fixed
+            .iter()
+            .map(|(p, line)| (*p, line.ell_coeffs_1.iter()))
+            .collect::<Vec<_>>();
+        // Ti: the accumulators for non-fixed Qi
+        let mut non_fixed_1 = non_fixed
+            .iter()
+            .map(|(p, q)| (*p, *q, G2HomProjective::<P>::new(q)))
+            .collect::<Vec<_>>();
+
+        // 1.1 f_1 = c_inv, as the most significant bit of u+1 is one.
+        let mut f_1 = c_inv;
+        for bit in BitIteratorBE::without_leading_zeros(P::ATE_LOOP_COUNT_1).skip(1) {
+            // double: f = f * f, and mul c_inv if bit == 1
+            f_1.square_in_place();
+            if bit {
+                f_1 *= c_inv;
+            }
+
+            // accumulate double lines (fixed and non-fixed)
+            for (p, line) in fixed_1.iter_mut() {
+                let coeffs = line.next().ok_or(PairingError::WrongInputLength)?;
+                ell::<P>(&mut f_1, coeffs, p);
+            }
+            for (p, _, T) in non_fixed_1.iter_mut() {
+                let coeffs = T.double_in_place();
+                ell::<P>(&mut f_1, &coeffs, p);
+            }
+
+            if bit {
+                // accumulate add lines (fixed and non-fixed)
+                for (p, line) in fixed_1.iter_mut() {
+                    let coeffs = line.next().ok_or(PairingError::WrongInputLength)?;
+                    ell::<P>(&mut f_1, coeffs, p);
+                }
+                for (p, q, T) in non_fixed_1.iter_mut() {
+                    let coeffs = T.add_in_place(q);
+                    ell::<P>(&mut f_1, &coeffs, p);
+                }
+            }
+        }
+
+        // 2. the second part: u^3-u^2-u in NAF
+        let mut fixed_2 = fixed
+            .iter()
+            .map(|(p, line)| (*p, line.ell_coeffs_2.iter()))
+            .collect::<Vec<_>>();
+        let mut non_fixed_2 = non_fixed
+            .iter()
+            .map(|(p, q)| (*p, *q, -**q, G2HomProjective::<P>::new(q)))
+            .collect::<Vec<_>>();
+
+        // 2.1 f_2 = c_inv, as the most significant digit is one.
+        let mut f_2 = c_inv;
+        for &digit in P::ATE_LOOP_COUNT_2.iter().rev().skip(1) {
+            // double: f = f * f, and mul c_inv if digit == 1, or c if digit == -1
+            f_2.square_in_place();
+            match digit {
+                1 => f_2 *= c_inv,
+                -1 => f_2 *= c,
+                _ => {}
+            }
+
+            // accumulate double lines (fixed and non-fixed)
+            for (p, line) in fixed_2.iter_mut() {
+                let coeffs = line.next().ok_or(PairingError::WrongInputLength)?;
+                ell::<P>(&mut f_2, coeffs, p);
+            }
+            for (p, _, _, T) in non_fixed_2.iter_mut() {
+                let coeffs = T.double_in_place();
+                ell::<P>(&mut f_2, &coeffs, p);
+            }
+
+            if digit != 0 {
+                // accumulate add lines with Qi or -Qi (fixed and non-fixed)
+                for (p, line) in fixed_2.iter_mut() {
+                    let coeffs = line.next().ok_or(PairingError::WrongInputLength)?;
+                    ell::<P>(&mut f_2, coeffs, p);
+                }
+                for (p, q, neg_q, T) in non_fixed_2.iter_mut() {
+                    let coeffs = if digit == 1 {
+                        T.add_in_place(q)
+                    } else {
+                        T.add_in_place(neg_q)
+                    };
+                    ell::<P>(&mut f_2, &coeffs, p);
+                }
+            }
+        }
+
+        // 3. final_f = f_1 * f_2^p
+        Ok(f_1 * f_2.frobenius_map(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::sample_pairing_equation_on;
+    use ark_bw6_761::{Config, BW6_761};
+    use ark_ec::pairing::Pairing;
+    use ark_std::{UniformRand, Zero};
+
+    #[test]
+    fn test_miller_loop() {
+        let rng = &mut ark_std::test_rng();
+        let (mut Ps, mut Qs) = sample_pairing_equation_on::<BW6_761, _>(4, rng);
+        // the pairs with zero point are skipped.
+        Ps.push(G1Affine::<Config>::zero());
+        Qs.push(G2Affine::<Config>::rand(rng));
+        Ps[1] = G1Affine::<Config>::rand(rng);
+
+        let pvk = PairingPVKey::<Config>::setup(Qs[0..2].to_vec());
+        let f = PairingProver::<Config>::merged_miller_loop(
+            &Ps[0..2],
+            &pvk.lines,
+            &Ps[2..],
+            &Qs[2..],
+            (Fp6::ONE, Fp6::ONE),
+        )
+        .unwrap();
+        assert_eq!(f, BW6_761::multi_miller_loop(Ps, Qs).0);
+    }
+
+    #[test]
+    fn test_prove_multi_pairing() {
+        let rng = &mut ark_std::test_rng();
+        let (Ps, Qs) = sample_pairing_equation_on::<BW6_761, _>(4, rng);
+        assert!(BW6_761::multi_pairing(Ps.clone(), Qs.clone()).is_zero());
+
+        // Q1,Q2,Q3 are fixed, and Q4 is non-fixed.
+        let pvk = PairingPVKey::<Config>::setup(Qs[0..3].to_vec());
+        let proof =
+            PairingProver::<Config>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[3]], vec![Qs[3]])
+                .unwrap();
+
+        // c^lambda = f, as wi is always one.
+        let params = Config::residue_params();
+        let f = BW6_761::multi_miller_loop(Ps.clone(), Qs.clone()).0;
+        assert_eq!(proof.wi, Fp6::ONE);
+        assert_eq!(proof.c.pow(params.lambda.to_u64_digits()), f);
+        assert_eq!(proof.c * proof.c_inv.unwrap(), Fp6::ONE);
+
+        // the pairing product is not 1
+        let res = PairingProver::<Config>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[0]], vec![Qs[3]]);
+        assert_eq!(res, Err(PairingError::NotRthResidue));
+
+        // mismatched lengths
+        let res = PairingProver::<Config>::prove(&pvk, Ps[0..2].to_vec(), vec![Ps[3]], vec![Qs[3]]);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+    }
+}
@@ -0,0 +1,19 @@
+use ark_ec::bw6::{BW6Config, G2Prepared};
+
+// To prove and verify the pairing, need precompute the miller lines for the fixed Qi.
+//
+// Each G2Prepared holds the lines of both parts of the miller loop.
+pub struct PairingPVKey<P: BW6Config> {
+    // precompute miller lines for fixed Qi.
+    //  Base line: [Q1,...,Qn]
+    pub lines: Vec<G2Prepared<P>>,
+}
+
+impl<P: BW6Config> PairingPVKey<P> {
+    pub fn setup(fixed_q: impl IntoIterator<Item = impl Into<G2Prepared<P>>>) -> Self {
+        // precompute lines for fixed Qi
+        let lines = fixed_q.into_iter().map(|q| q.into()).collect();
+
+        Self { lines }
+    }
+}
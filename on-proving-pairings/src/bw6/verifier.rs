@@ -0,0 +1,108 @@
+use crate::bw6::params::BW6ResidueConfig;
+use crate::bw6::prover::PairingProver;
+use crate::bw6::setup::PairingPVKey;
+use crate::error::PairingError;
+use crate::residue_proof::PairingProof;
+use ark_ec::bw6::{BW6Config, G1Affine, G2Affine};
+use ark_ff::fields::fp6_2over3::Fp6;
+use ark_ff::Field;
+use std::marker::PhantomData;
+
+pub struct PairingVerifier<P: BW6Config>(PhantomData<P>);
+
+impl<P: BW6ResidueConfig> PairingVerifier<P> {
+    // Verify multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=1` with the pairing proof.
+    //
+    // Only public data and the witness (c, wi) are used:
+    //  1. redo the merged two-part miller loop with c_inv folded in, namely final_f = f * c_inv^lambda
+    //  2. check final_f * wi equals one
+    //
+    // params:
+    //  @pvk: precompute miller lines for fixed [Q1,...,Qn]
+    //  @eval_points: [P1,...,Pn]. which maps with fixed [Q1,...,Qn]
+    //  @non_fixed_points: [P'1,...,P'm]. which maps with non-fixed [Q'1,...,Q'm]
+    //  @non_fixed_q: [Q'1,...,Q'm]
+    //  @proof: pairing proof (c, wi), satisfying c^lambda = f*wi
+    //
+    // return: Ok(false) if the check fails, or the error if the inputs or proof are malformed.
+    pub fn verify(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        proof: &PairingProof<Fp6<P::Fp6Config>>,
+    ) -> Result<bool, PairingError> {
+        // check the hint c_inv if provided, otherwise compute it.
+        let c_inv = match proof.c_inv {
+            Some(c_inv) if proof.c * c_inv == Fp6::ONE => c_inv,
+            Some(_) => return Err(PairingError::InvalidWitness),
+            None => proof.c.inverse().ok_or(PairingError::InvalidWitness)?,
+        };
+        // reject the proof if wi^{h_bad} != 1.
+        P::residue_params().check_wi(&proof.wi)?;
+
+        // 1. final_f = f * c_inv^lambda
+        let final_f = PairingProver::<P>::merged_miller_loop(
+            &eval_points,
+            &pvk.lines,
+            &non_fixed_points,
+            &non_fixed_q,
+            (proof.c, c_inv),
+        )?;
+
+        // 2. c_inv^lambda * f * wi = 1
+        Ok(final_f * proof.wi == Fp6::ONE)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::residue_proof::test::{check_forged_wi, check_verify};
+    use crate::utils::sample_pairing_equation_on;
+    use ark_bw6_761::{Config, BW6_761};
+    use ark_ec::pairing::Pairing;
+    use ark_ec::{AffineRepr, CurveGroup};
+
+    #[test]
+    fn test_verify() {
+        let rng = &mut ark_std::test_rng();
+        let (Ps, Qs) = sample_pairing_equation_on::<BW6_761, _>(4, rng);
+        let pvk = PairingPVKey::<Config>::setup(Qs[0..3].to_vec());
+        let proof =
+            PairingProver::<Config>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[3]], vec![Qs[3]])
+                .unwrap();
+        let verify = |P4: G1Affine<Config>, proof: &PairingProof<ark_bw6_761::Fq6>| {
+            PairingVerifier::<Config>::verify(&pvk, Ps[0..3].to_vec(), vec![P4], vec![Qs[3]], proof)
+        };
+        let P4 = (Ps[3] + G1Affine::<Config>::generator()).into_affine();
+        check_verify(verify, &proof, Ps[3], P4);
+
+        // wrong length
+        let res =
+            PairingVerifier::<Config>::verify(&pvk, Ps[0..2].to_vec(), vec![], vec![], &proof);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+    }
+
+    #[test]
+    fn test_verify_with_forged_wi() {
+        let rng = &mut ark_std::test_rng();
+        let (Ps, Qs) = sample_pairing_equation_on::<BW6_761, _>(4, rng);
+        let pvk = PairingPVKey::<Config>::setup(Qs[0..3].to_vec());
+        let proof =
+            PairingProver::<Config>::prove(&pvk, Ps[0..3].to_vec(), vec![Ps[3]], vec![Qs[3]])
+                .unwrap();
+        let verify = |P4: G1Affine<Config>, proof: &PairingProof<ark_bw6_761::Fq6>| {
+            PairingVerifier::<Config>::verify(&pvk, Ps[0..3].to_vec(), vec![P4], vec![Qs[3]], proof)
+        };
+
+        let P4 = (Ps[3] + G1Affine::<Config>::generator()).into_affine();
+        let wrong_f = BW6_761::multi_miller_loop(
+            Ps[0..3].iter().copied().chain([P4]).collect::<Vec<_>>(),
+            Qs.clone(),
+        )
+        .0;
+        let lambda = Config::residue_params().lambda.to_u64_digits();
+        check_forged_wi(verify, &proof, (Ps[3], P4), wrong_f, &lambda, rng);
+    }
+}
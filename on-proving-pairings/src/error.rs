@@ -0,0 +1,54 @@
+use std::fmt;
+
+// Errors of setup, prover and verifier for proving pairings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PairingError {
+    // The miller loop output f isn't r-th residue, namely the pairing product is not 1.
+    NotRthResidue,
+    // The lengths of eval points, lines and Qi mismatch.
+    WrongInputLength,
+    // Failed to compute the cubic root with tonelli-shanks.
+    CubeRootFailed,
+    // The witness (c, wi) or its hint is malformed.
+    InvalidWitness,
+    // The target T isn't in GT, or f_T is zero, which has no inverse.
+    InvalidTarget,
+}
+
+impl fmt::Display for PairingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PairingError::NotRthResidue => {
+                write!(f, "f is not r-th residue, pairing product is not 1")
+            }
+            PairingError::WrongInputLength => write!(f, "the lengths of inputs mismatch"),
+            PairingError::CubeRootFailed => write!(f, "failed to compute the cubic root"),
+            PairingError::InvalidWitness => write!(f, "the witness is invalid"),
+            PairingError::InvalidTarget => write!(f, "the target is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for PairingError {}
+
+// Errors of generating the λ residues parameters from the BN parameter x.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamsError {
+    // p or r isn't prime, namely x doesn't give a BN curve.
+    NotPrime(&'static str),
+    // One of the conditions of 4.3.1 in the paper fails.
+    Condition(&'static str),
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamsError::NotPrime(name) => write!(f, "{} is not prime", name),
+            ParamsError::Condition(condition) => {
+                write!(f, "the condition `{}` doesn't hold", condition)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParamsError {}
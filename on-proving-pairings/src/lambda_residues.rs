@@ -1,131 +1,70 @@
-use crate::params;
-use crate::params::MODULUS;
+use crate::error::{PairingError, ParamsError};
+use crate::params::{self, BnResidueConfig};
+use crate::utils::pow_windowed;
 use ark_bn254::Fq12;
-use ark_ff::Field;
-use ark_std::UniformRand;
+use ark_ec::bn::BnConfig;
+use ark_ec::CurveConfig;
+use ark_ff::fields::fp12_2over3over2::Fp12;
+use ark_ff::{Field, PrimeField, UniformRand};
 use num_bigint::BigUint;
-use num_traits::{One, ToPrimitive};
-use rand_chacha::rand_core::SeedableRng;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
-use std::ops::Deref;
 
 // satisfying c^lambda = f * wi
-pub struct LambdaResidues {
-    pub c: Fq12,
-    pub wi: Fq12,
+pub struct LambdaResidues<F: Field = Fq12> {
+    pub c: F,
+    // wi = w^i, where w is the canonical root of unity, e.g. the 27-th root `params::W` of BN254
+    pub wi: F,
+    // i ∈ [0, d), e.g. {0,1,2} for BN254
+    pub wi_index: u8,
 }
 
 impl LambdaResidues {
-    // Computing λ residues over BN curve
-    // Input:
-    //      f: output of a Miller loop.
-    //          It's always be r-th and m′-th residue, but it might not be a cubic residue.
-    // Output:
-    //      c and wi,
-    //      satisfying c^lambda = f * wi
-    //
-    // Ref: Algorithm 5 of [On Proving Pairings](https://eprint.iacr.org/2024/640.pdf)
-    pub fn finding_c(f: Fq12) -> Self {
-        let s = 3_u32;
-        let exp = MODULUS.pow(12_u32) - 1_u32;
-        let h = &exp / params::R.deref();
-        let t = &exp / 3_u32.pow(s);
-        let k = (&t + 1_u32) / 3_u32;
-        let m = params::LAMBDA.deref() / params::R.deref();
-        let d = 3_u32;
-        let mm = &m / d;
-
-        let mut prng = ChaCha20Rng::seed_from_u64(0);
-        let cofactor_cubic = 3_u32.pow(s - 1) * &t;
-
-        // Find C. See more: 4.3.2 Finding c
-        // make f is r-th residue, but it's not cubic residue
-        assert_eq!(f.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
-        assert_ne!(f.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-
-        // sample a proper scalar w which is cubic non-residue
-        let w = {
-            let (mut w, mut z) = (ark_bn254::Fq12::ONE, ark_bn254::Fq12::ONE);
-            while w == ark_bn254::Fq12::ONE {
-                // choose z which is 3-th non-residue
-                let mut legendre = ark_bn254::Fq12::ONE;
-                while legendre == ark_bn254::Fq12::ONE {
-                    z = ark_bn254::Fq12::rand(&mut prng);
-                    legendre = z.pow(cofactor_cubic.to_u64_digits());
-                }
-                // obtain w which is t-th power of z
-                w = z.pow(t.to_u64_digits());
-            }
-            w
-        };
-        // make sure 27-th root w, is 3-th non-residue and r-th residue
-        assert_ne!(w.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-        assert_eq!(w.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
-
-        // just two option, w and w^2, since w^3 must be cubic residue, leading f*w^3 must not be cubic residue
-        let mut wi = w;
-        if (f * wi).pow(cofactor_cubic.to_u64_digits()) != ark_bn254::Fq12::ONE {
-            assert_eq!(
-                (f * w * w).pow(cofactor_cubic.to_u64_digits()),
-                ark_bn254::Fq12::ONE
-            );
-            wi = w * w;
-        }
-        assert_eq!(wi.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
-
-        assert_eq!(params::LAMBDA.clone(), &d * &mm * params::R.deref());
-        // f1 is scaled f
-        let f1 = f * wi;
-
-        // r-th root of f1, say f2
-        let r_inv = params::R.deref().modinv(&h).unwrap();
-        assert_ne!(r_inv, BigUint::one());
-        let f2 = f1.pow(r_inv.to_u64_digits());
-        assert_ne!(f2, ark_bn254::Fq12::ONE);
-
-        // m'-th root of f, say f3
-        let mm_inv = mm.modinv(&(params::R.deref() * h)).unwrap();
-        assert_ne!(mm_inv, BigUint::one());
-        let f3 = f2.pow(mm_inv.to_u64_digits());
-        assert_eq!(f3.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-        assert_ne!(f3, ark_bn254::Fq12::ONE);
-
-        // d-th (cubic) root, say c
-        let c = Self::tonelli_shanks_cubic(f3, w, s, t, k);
-        assert_ne!(c, ark_bn254::Fq12::ONE);
-        assert_eq!(c.pow(params::LAMBDA.clone().to_u64_digits()), f * wi);
-
-        Self { c, wi }
+    // Computing λ residues over BN254, see `BnResidueParams::finding_c`.
+    pub fn finding_c(f: Fq12) -> Result<Self, PairingError> {
+        ark_bn254::Config::residue_params().finding_c(f)
     }
 
     // Tonelli-Shanks for cube roots
     // Inputs:
     //         a: Fp12 which is cubic residue
     //         c: random Fp12 which is cubic non-residue
-    //         s: satisfying p^12 - 1 = 3^s * t
+    //         s: satisfying p^12 - 1 = 3^s * t, where s ≥ 1
     //         t: satisfying p^12 - 1 = 3^s * t
     //         k: k = (t + 1) // 3
     //
+    // Output:
+    //         the cubic root of a, or `CubeRootFailed` if a isn't cubic residue or s is 0.
+    //
     // Ref: Table 3 from https://eprint.iacr.org/2009/457.pdf
     // Ref: Algorithm 4 of [On Proving Pairings](https://eprint.iacr.org/2024/640.pdf)
-    pub fn tonelli_shanks_cubic(a: Fq12, c: Fq12, s: u32, t: BigUint, k: BigUint) -> Fq12 {
+    pub fn tonelli_shanks_cubic(
+        a: Fq12,
+        c: Fq12,
+        s: u32,
+        t: BigUint,
+        k: BigUint,
+    ) -> Result<Fq12, PairingError> {
+        // 3 ∤ p^12 - 1 leaves no cube roots of unity to search.
+        if s == 0 {
+            return Err(PairingError::CubeRootFailed);
+        }
+        // 3^i over BigUint, as 3^s overflows u32 for s ≥ 21.
+        let three_pow = |i: u32| BigUint::from(3_u32).pow(i);
+
         // r = a^t
         let mut r = a.pow(t.to_u64_digits());
-        let e = 3_u32.pow(s - 1);
-        let exp = 3_u32.pow(s) * &t;
+        let e = three_pow(s - 1);
 
         // compute cubic root of (a^t)^-1, say h
-        let mut h = ark_bn254::Fq12::ONE;
-        let cc = c.pow([e as u64]);
-        let mut c = c.inverse().unwrap();
-
-        for i in 1..(s as i32) {
-            let delta = (s as i32) - i - 1;
-            let d = if delta < 0 {
-                r.pow((&exp / 3_u32.pow((-delta) as u32)).to_u64_digits())
-            } else {
-                r.pow([3_u32.pow(delta as u32).to_u64().unwrap()])
-            };
+        let mut h = Fq12::ONE;
+        let cc = c.pow(e.to_u64_digits());
+        let mut c = c.inverse().ok_or(PairingError::CubeRootFailed)?;
+
+        for i in 1..s {
+            let d = r.pow(three_pow(s - i - 1).to_u64_digits());
             if d == cc {
                 (h, r) = (h * c, r * c.pow([3 as u64]));
             } else if d == cc.pow([2_u64]) {
@@ -138,16 +77,396 @@ impl LambdaResidues {
         // r = a^k * h
         r = a.pow(k.to_u64_digits()) * h;
         if t == 3_u32 * k + 1_u32 {
-            r = r.inverse().unwrap();
+            r = r.inverse().ok_or(PairingError::CubeRootFailed)?;
         }
 
-        assert_eq!(r.pow([3 as u64]), a);
-        r
+        if r.pow([3 as u64]) != a {
+            return Err(PairingError::CubeRootFailed);
+        }
+        Ok(r)
+    }
+}
+
+// λ residues parameters of BN curves, derived from x and p.
+//
+// Write p^12 - 1 = r * h, and h = h_bad * h', where h_bad collects all the primes of gcd(λ, h),
+// and w is a canonical generator of the h_bad-th roots of unity, e.g. the 27-th root `params::W` of BN254.
+// As x -> x^λ maps <w> onto its subgroup of index d = gcd(λ, h_bad), exactly one of f, f*w, ..., f*w^{d-1}
+// is a λ-th residue. So wi is carried as an index i ∈ [0, d), where d = 3 for BN254.
+//
+// h_bad stays small for BN curves, so the discrete log over <w> is taken by a linear search.
+pub struct BnResidueParams<P: BnConfig> {
+    // λ = 6x + 2 + p - p^2 + p^3, which is a multiple of r
+    pub lambda: BigUint,
+    // h = (p^12 - 1) / r
+    pub h: BigUint,
+    // the part of h sharing primes with λ, namely the order of w
+    pub h_bad: u64,
+    // d = gcd(λ, h_bad), the number of scalars wi
+    pub d: u64,
+    // the generator of the h_bad-th roots of unity
+    pub w: Fp12<P::Fp12Config>,
+    // the allowed scalars wi = w^i, where i ∈ [0, d)
+    pub wi: Vec<Fp12<P::Fp12Config>>,
+    // h' = h / h_bad, as u64 limbs
+    h_dash: Vec<u64>,
+    // h'^{-1} mod h_bad
+    h_dash_inv: u64,
+    // λ^{-1} mod h', which is 0 mod h_bad as well, as u64 limbs
+    lambda_inv: Vec<u64>,
+    // (λ/d)^{-1} mod h_bad/d
+    lambda_div_d_inv: u64,
+    // |6x + 2|, as u64 limbs
+    e: Vec<u64>,
+    // (h * k)^{-1} mod r, as u64 limbs, which takes the target T to f_T of `PairingTarget`.
+    //  The final exponentiation of `Bn` is f -> f^{h * k}, where k = 2x(6x^2 + 3x + 1) from its hard part.
+    //  Ref: Fuentes-Castañeda, Knapp and Rodríguez-Henríquez, Faster hashing to G2
+    pub(crate) hk_inv: Vec<u64>,
+}
+
+impl<P: BnConfig> BnResidueParams<P> {
+    // Derive the parameters from x and p, where w is sampled with a fixed seed.
+    pub fn new() -> Result<Self, ParamsError> {
+        Self::derive(None)
+    }
+
+    // Derive the parameters from x and p, with the given generator w of the h_bad-th roots of unity.
+    pub fn with_w(w: Fp12<P::Fp12Config>) -> Result<Self, ParamsError> {
+        Self::derive(Some(w))
+    }
+
+    fn derive(w: Option<Fp12<P::Fp12Config>>) -> Result<Self, ParamsError> {
+        let one = BigUint::one();
+        let p: BigUint = P::Fp::MODULUS.into();
+        let r: BigUint = <P::G1Config as CurveConfig>::ScalarField::MODULUS.into();
+        let x = P::X
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, limb| (acc << 64) + limb);
+
+        // λ = 6x + 2 + p - p^2 + p^3, where x = -|x| if `X_IS_NEGATIVE`, and e = |6x + 2|.
+        let (e, lambda) = if P::X_IS_NEGATIVE {
+            let e = &x * 6_u32 - 2_u32;
+            let lambda = &p + p.pow(3) - p.pow(2) - &e;
+            (e, lambda)
+        } else {
+            let e = &x * 6_u32 + 2_u32;
+            let lambda = &e + &p + p.pow(3) - p.pow(2);
+            (e, lambda)
+        };
+        if !(&lambda % &r).is_zero() {
+            return Err(ParamsError::Condition("r | λ"));
+        }
+        let h = (p.pow(12) - &one) / &r;
+
+        // strip all the primes of gcd(λ, h) from h
+        let mut h_dash = h.clone();
+        loop {
+            let g = h_dash.gcd(&lambda);
+            if g == one {
+                break;
+            }
+            h_dash /= g;
+        }
+        let h_bad = (&h / &h_dash)
+            .to_u64()
+            .ok_or(ParamsError::Condition("h_bad fits in u64"))?;
+        let d = (&lambda % h_bad).to_u64().unwrap().gcd(&h_bad);
+        // wi_index is carried as u8
+        if d > 256 {
+            return Err(ParamsError::Condition("d ≤ 256"));
+        }
+
+        let modinv = |a: &BigUint, m: u64| match m {
+            1 => 0,
+            _ => (a % m).modinv(&BigUint::from(m)).unwrap().to_u64().unwrap(),
+        };
+        let h_dash_inv = modinv(&h_dash, h_bad);
+        let lambda_div_d_inv = modinv(&(&lambda / d), h_bad / d);
+        // CRT: λ^{-1} mod h', and 0 mod h_bad
+        let h_bad_big = BigUint::from(h_bad);
+        let lambda_inv =
+            lambda.modinv(&h_dash).unwrap() * h_bad_big.modinv(&h_dash).unwrap() * &h_bad_big % &h;
+
+        // k = 2x(6x^2 + 3x + 1), which must be coprime to r along with h.
+        //  For negative x, k = -2|x|(6x^2 - 3|x| + 1), namely r - (|k| mod r) mod r.
+        let hk = if P::X_IS_NEGATIVE {
+            let k = 2_u32 * &x * (6_u32 * &x * &x - 3_u32 * &x + 1_u32);
+            (&r - &h * k % &r) % &r
+        } else {
+            let k = 2_u32 * &x * (6_u32 * &x * &x + 3_u32 * &x + 1_u32);
+            &h * k % &r
+        };
+        let hk_inv = hk
+            .modinv(&r)
+            .ok_or(ParamsError::Condition("gcd(h * k, r) = 1"))?;
+
+        // w: a generator of the h_bad-th roots of unity
+        let w = match w {
+            Some(w) if Self::is_generator(&w, h_bad) => w,
+            Some(_) => return Err(ParamsError::Condition("w is of order h_bad")),
+            None => {
+                let cofactor = (p.pow(12) - &one) / h_bad;
+                let rng = &mut ChaCha20Rng::seed_from_u64(0);
+                loop {
+                    let w = Fp12::<P::Fp12Config>::rand(rng).pow(cofactor.to_u64_digits());
+                    if Self::is_generator(&w, h_bad) {
+                        break w;
+                    }
+                }
+            }
+        };
+        let wi = (0..d).map(|i| w.pow([i])).collect();
+
+        Ok(Self {
+            lambda,
+            h,
+            h_bad,
+            d,
+            w,
+            wi,
+            h_dash: h_dash.to_u64_digits(),
+            h_dash_inv,
+            lambda_inv: lambda_inv.to_u64_digits(),
+            lambda_div_d_inv,
+            e: e.to_u64_digits(),
+            hk_inv: hk_inv.to_u64_digits(),
+        })
+    }
+
+    // w is of order n, namely w^n = 1 and w^{n/q} != 1 for each prime q | n.
+    fn is_generator(w: &Fp12<P::Fp12Config>, n: u64) -> bool {
+        if w.pow([n]) != Fp12::ONE {
+            return false;
+        }
+        let (mut m, mut q) = (n, 2);
+        while m > 1 {
+            if m % q == 0 {
+                if w.pow([n / q]) == Fp12::ONE {
+                    return false;
+                }
+                while m % q == 0 {
+                    m /= q;
+                }
+            }
+            q += 1;
+        }
+        true
+    }
+
+    // Computing λ residues over BN curve
+    // Input:
+    //      f: output of a Miller loop.
+    //          It's always be r-th and m′-th residue, but it might not be a λ-th residue.
+    // Output:
+    //      c and wi, where wi is one of w^0, ..., w^{d-1} for the canonical root w,
+    //      satisfying c^lambda = f * wi
+    //      or `NotRthResidue` if f isn't r-th residue, namely the pairing product is not 1.
+    //
+    // As f^h = 1, f lies in the cyclic group of order h_bad * h', namely f = w^e * f', where f'^{h'} = 1.
+    // Rather than taking the r-th, m'-th and d-th roots one by one, it takes two exponentiations:
+    //  1. f^{h'} = w^{e * h'}, which leads to e and wi.
+    //  2. c = f^{λ^{-1} mod h'} * w^k, where w^{k * λ} = w^e * wi.
+    //
+    // Ref: Algorithm 5 of [On Proving Pairings](https://eprint.iacr.org/2024/640.pdf)
+    pub fn finding_c(
+        &self,
+        f: Fp12<P::Fp12Config>,
+    ) -> Result<LambdaResidues<Fp12<P::Fp12Config>>, PairingError> {
+        // 1. f is r-th residue iff f^{h'} lies in <w>, since f^h = (f^{h'})^{h_bad}.
+        let u = pow_windowed(f, &self.h_dash);
+        let mut w_pow = Fp12::ONE;
+        let mut log_u = None;
+        for i in 0..self.h_bad {
+            if w_pow == u {
+                log_u = Some(i as u128);
+                break;
+            }
+            w_pow *= self.w;
+        }
+        let log_u = log_u.ok_or(PairingError::NotRthResidue)?;
+
+        // 2. the h_bad-th root part of f is w^e, where e = log_w(u) * h'^{-1} mod h_bad
+        let (h_bad, d) = (self.h_bad as u128, self.d as u128);
+        let e = log_u * self.h_dash_inv as u128 % h_bad;
+
+        // d options: exactly one of f, f*w, ..., f*w^{d-1} is λ-th residue.
+        //  f*w^i is λ-th residue iff d | e + i
+        let wi_index = (d - e % d) % d;
+        let wi = self.wi[wi_index as usize];
+
+        // 3. λ-th root of f*wi, say c = f^{λ^{-1} mod h'} * w^k
+        //  where k * λ = e + i mod h_bad, namely k = (e + i)/d * (λ/d)^{-1} mod h_bad/d.
+        let k = (e + wi_index) / d * self.lambda_div_d_inv as u128 % (h_bad / d);
+        let c = pow_windowed(f, &self.lambda_inv) * self.w.pow([k as u64]);
+
+        // c^λ = c^{6x+2} * c^p * c^{-p^2} * c^{p^3}, where c^{6x+2} moves to the right for negative x.
+        let mut lhs = c.frobenius_map(1) * c.frobenius_map(3);
+        let mut rhs = f * wi * c.frobenius_map(2);
+        if P::X_IS_NEGATIVE {
+            rhs *= pow_windowed(c, &self.e);
+        } else {
+            lhs *= pow_windowed(c, &self.e);
+        }
+        if lhs != rhs {
+            return Err(PairingError::CubeRootFailed);
+        }
+
+        Ok(LambdaResidues {
+            c,
+            wi,
+            wi_index: wi_index as u8,
+        })
+    }
+
+    // wi = w^i, or `InvalidWitness` if i isn't in [0, d).
+    pub fn wi(&self, index: u8) -> Result<Fp12<P::Fp12Config>, PairingError> {
+        self.wi
+            .get(index as usize)
+            .copied()
+            .ok_or(PairingError::InvalidWitness)
+    }
+
+    // The index i of wi = w^i, or None if wi isn't one of the allowed scalars.
+    pub fn wi_index(&self, wi: &Fp12<P::Fp12Config>) -> Option<u8> {
+        self.wi.iter().position(|w| w == wi).map(|i| i as u8)
+    }
+}
+
+impl BnResidueParams<ark_bn254::Config> {
+    // The parameters of BN254 from the constants generated by `bn_params`, skipping `derive`.
+    pub fn bn254() -> Self {
+        Self {
+            lambda: params::LAMBDA.clone(),
+            h: params::H.clone(),
+            h_bad: params::H_BAD,
+            d: params::D,
+            w: params::W,
+            wi: params::WI.to_vec(),
+            h_dash: params::T_DASH.to_vec(),
+            h_dash_inv: params::T_DASH_INV_MOD_27,
+            lambda_inv: params::LAMBDA_INV.to_vec(),
+            lambda_div_d_inv: params::LAMBDA_DIV_3_INV_MOD_9,
+            e: params::E_LIMBS.to_vec(),
+            hk_inv: params::HK_INV_LIMBS.to_vec(),
+        }
+    }
+}
+
+// λ residues parameters of the curves whose d-th roots are absorbed by wi, e.g. BLS12 and BW6 curves.
+//
+// Write p^k - 1 = r * h, and h = h_bad * h', where h_bad collects all the primes of gcd(λ, h),
+// namely gcd(λ, h') = 1. As f^h = 1, f = f_bad * f' with f_bad^{h_bad} = 1 and f'^{h'} = 1. So
+//      c = f^{λ^{-1} mod h'}, wi = f_bad^{-1}, satisfying c^lambda = f * wi.
+//
+// It's sound for any wi of order dividing h_bad, since h_bad | h leads to f^h = c^{λh} = 1.
+// Unlike BN254, there's no cube root to compute, and h_bad stays small (e.g. 67 bits for BLS12-381).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResidueParams {
+    // λ, which is a multiple of r
+    pub lambda: BigUint,
+    // h = (p^k - 1) / r
+    pub h: BigUint,
+    // the part of h sharing primes with λ
+    pub h_bad: BigUint,
+    // h' = h / h_bad, as u64 limbs
+    h_dash: Vec<u64>,
+    // h'^{-1} mod h_bad, as u64 limbs
+    h_dash_inv: Vec<u64>,
+    // λ^{-1} mod h', which is 0 mod h_bad as well, as u64 limbs
+    lambda_inv: Vec<u64>,
+}
+
+impl ResidueParams {
+    // params:
+    //  @p: modulus of base field
+    //  @r: modulus of scalar field
+    //  @k: embedding degree
+    //  @lambda: λ, which must be a multiple of r
+    pub fn new(p: &BigUint, r: &BigUint, k: u32, lambda: BigUint) -> Result<Self, ParamsError> {
+        let one = BigUint::one();
+        let exp = p.pow(k) - &one;
+        if !(&exp % r).is_zero() {
+            return Err(ParamsError::Condition("r | p^k - 1"));
+        }
+        if !(&lambda % r).is_zero() {
+            return Err(ParamsError::Condition("r | λ"));
+        }
+        let h = exp / r;
+
+        // strip all the primes of gcd(λ, h) from h
+        let mut h_dash = h.clone();
+        loop {
+            let g = h_dash.gcd(&lambda);
+            if g == one {
+                break;
+            }
+            h_dash /= g;
+        }
+        let h_bad = &h / &h_dash;
+
+        let h_dash_inv = if h_bad == one {
+            BigUint::zero()
+        } else {
+            h_dash.modinv(&h_bad).unwrap()
+        };
+        // CRT: λ^{-1} mod h', and 0 mod h_bad
+        let lambda_inv = if h_bad == one {
+            lambda.modinv(&h_dash).unwrap()
+        } else {
+            lambda.modinv(&h_dash).unwrap() * h_bad.modinv(&h_dash).unwrap() * &h_bad % &h
+        };
+
+        Ok(Self {
+            lambda,
+            h,
+            h_dash: h_dash.to_u64_digits(),
+            h_dash_inv: h_dash_inv.to_u64_digits(),
+            lambda_inv: lambda_inv.to_u64_digits(),
+            h_bad,
+        })
+    }
+
+    // Computing λ residues
+    // Input:
+    //      f: output of a Miller loop.
+    // Output:
+    //      (c, wi), where wi^{h_bad} = 1, satisfying c^lambda = f * wi
+    //      or `NotRthResidue` if f isn't r-th residue, namely the pairing product is not 1.
+    pub fn finding_c<F: Field>(&self, f: F) -> Result<(F, F), PairingError> {
+        // 1. f^h = 1 iff u = f^{h'} satisfies u^{h_bad} = 1
+        let u = pow_windowed(f, &self.h_dash);
+        if u.pow(self.h_bad.to_u64_digits()) != F::one() {
+            return Err(PairingError::NotRthResidue);
+        }
+
+        // 2. f_bad = u^{h'^{-1} mod h_bad}, and wi = f_bad^{-1}
+        let wi = u
+            .pow(&self.h_dash_inv)
+            .inverse()
+            .ok_or(PairingError::NotRthResidue)?;
+
+        // 3. c = f^{λ^{-1} mod h'}, since (f * wi)^{h'} = 1.
+        let c = pow_windowed(f, &self.lambda_inv);
+        debug_assert_eq!(c.pow(self.lambda.to_u64_digits()), f * wi);
+
+        Ok((c, wi))
+    }
+
+    // Soundness check: wi^{h_bad} = 1.
+    //
+    // Otherwise a malicious prover could pick an arbitrary wi, say wi = c^lambda / f,
+    // and absorb any discrepancy into it.
+    pub fn check_wi<F: Field>(&self, wi: &F) -> Result<(), PairingError> {
+        if wi.pow(self.h_bad.to_u64_digits()) != F::one() {
+            return Err(PairingError::InvalidWitness);
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use super::*;
     use crate::params::MODULUS;
     use std::ops::Deref;
@@ -161,6 +480,43 @@ mod test {
     use rand::SeedableRng;
     use rand_chacha::ChaCha20Rng;
 
+    // The parameters of the curve P, whose w is of order h_bad and the scalars wi are its first d powers.
+    pub(crate) fn test_residue_params_on<P: BnResidueConfig>(h_bad: u64, d: u64) {
+        let params = P::residue_params();
+        assert_eq!((params.h_bad, params.d), (h_bad, d));
+        assert_eq!(params.wi.len() as u64, d);
+        assert_eq!(params.wi[0], Fp12::ONE);
+        assert_eq!(params.w.pow([h_bad]), Fp12::ONE);
+        let r: BigUint = <P::G1Config as CurveConfig>::ScalarField::MODULUS.into();
+        assert!((&params.lambda % r).is_zero());
+    }
+
+    pub(crate) fn test_finding_c_for_each_wi_on<P: BnResidueConfig>(d: u64) {
+        let params = P::residue_params();
+        let rng = &mut ark_std::test_rng();
+        let r: BigUint = <P::G1Config as CurveConfig>::ScalarField::MODULUS.into();
+
+        // f * w^j for j ∈ [0, d) falls into the d classes of λ-th residue, one wi for each.
+        let f = Fp12::<P::Fp12Config>::rand(rng).pow(r.to_u64_digits());
+        let mut wi_indices = vec![];
+        for j in 0..d {
+            let fj = f * params.w.pow([j]);
+            let witness = params.finding_c(fj).unwrap();
+            assert_eq!(
+                witness.c.pow(params.lambda.to_u64_digits()),
+                fj * witness.wi
+            );
+            assert_eq!(params.wi_index(&witness.wi), Some(witness.wi_index));
+            wi_indices.push(witness.wi_index);
+        }
+        wi_indices.sort();
+        assert_eq!(wi_indices, (0..d as u8).collect::<Vec<u8>>());
+
+        // f isn't r-th residue
+        let res = params.finding_c(Fp12::<P::Fp12Config>::rand(rng));
+        assert_eq!(res.err(), Some(PairingError::NotRthResidue));
+    }
+
     #[test]
     fn test_compute_c_wi() {
         // 1. constant params
@@ -235,7 +591,157 @@ mod test {
         let f3 = f2.pow(mm_inv.to_u64_digits());
 
         // d-th (cubic) root, say c
-        let c = LambdaResidues::tonelli_shanks_cubic(f3, w, s, t, k);
+        let c = LambdaResidues::tonelli_shanks_cubic(f3, w, s, t, k).unwrap();
         assert_eq!(c.pow(params::LAMBDA.deref().to_u64_digits()), f * wi);
     }
+
+    #[test]
+    fn test_finding_c_with_wrong_f() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        // random f is not r-th residue
+        let f = ark_bn254::Fq12::rand(&mut prng);
+        assert_eq!(
+            LambdaResidues::finding_c(f).err(),
+            Some(PairingError::NotRthResidue)
+        );
+    }
+
+    #[test]
+    fn test_tonelli_shanks_cubic_with_non_residue() {
+        let s = 3_u32;
+        let exp = MODULUS.pow(12) - 1_u32;
+        let t = &exp / 3_u32.pow(s);
+        let k = (&t + 1_u32) / 3_u32;
+        let cofactor_cubic = 3_u32.pow(s - 1) * &t;
+
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let mut a = ark_bn254::Fq12::rand(&mut prng);
+        while a.pow(cofactor_cubic.to_u64_digits()) == ark_bn254::Fq12::ONE {
+            a = ark_bn254::Fq12::rand(&mut prng);
+        }
+
+        // a is cubic non-residue, so it has no cubic root.
+        let res = LambdaResidues::tonelli_shanks_cubic(a, a, s, t, k);
+        assert_eq!(res, Err(PairingError::CubeRootFailed));
+    }
+
+    #[test]
+    fn test_tonelli_shanks_cubic_with_wrong_s() {
+        let exp = MODULUS.pow(12) - 1_u32;
+        let t = &exp / 27_u32;
+        let k = (&t + 1_u32) / 3_u32;
+        let a = ark_bn254::Fq12::rand(&mut ChaCha20Rng::seed_from_u64(0)).pow([3_u64]);
+
+        // s = 0 leaves no cube roots of unity, and 3^s exceeds u32 for s = 21.
+        for s in [0, 21] {
+            let res = LambdaResidues::tonelli_shanks_cubic(a, a, s, t.clone(), k.clone());
+            assert_eq!(res, Err(PairingError::CubeRootFailed));
+        }
+    }
+
+    #[test]
+    fn test_invalid_residue_params() {
+        // w^3 is of order 9 rather than 27
+        let res = BnResidueParams::<ark_bn254::Config>::with_w(params::W.pow([3_u64]));
+        assert_eq!(
+            res.err(),
+            Some(ParamsError::Condition("w is of order h_bad"))
+        );
+
+        // λ isn't a multiple of r
+        let lambda = params::LAMBDA.deref() + 1_u32;
+        let res = ResidueParams::new(&MODULUS, &params::R, 12, lambda);
+        assert_eq!(res, Err(ParamsError::Condition("r | λ")));
+
+        // the embedding degree of BN254 is 12, not 6
+        let res = ResidueParams::new(&MODULUS, &params::R, 6, params::LAMBDA.clone());
+        assert_eq!(res, Err(ParamsError::Condition("r | p^k - 1")));
+    }
+
+    #[test]
+    fn test_finding_c_for_each_wi() {
+        let s = 3_u32;
+        let exp = MODULUS.pow(12) - 1_u32;
+        let t = &exp / 3_u32.pow(s);
+        let cofactor_cubic = 3_u32.pow(s - 1) * &t;
+
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        // sample a miller loop result f which is r-th residue, and a 27-th root w which is cubic non-residue.
+        let f = ark_bn254::Fq12::rand(&mut prng).pow(params::R.deref().to_u64_digits());
+        let mut w = ark_bn254::Fq12::ONE;
+        while w.pow(cofactor_cubic.to_u64_digits()) == ark_bn254::Fq12::ONE {
+            w = ark_bn254::Fq12::rand(&mut prng).pow(t.to_u64_digits());
+        }
+
+        // f, f*w and f*w^2 fall into the three classes of cubic residue.
+        let mut wis = vec![];
+        for fi in [f, f * w, f * w * w] {
+            let witness = LambdaResidues::finding_c(fi).unwrap();
+            assert_eq!(
+                witness.c.pow(params::LAMBDA.deref().to_u64_digits()),
+                fi * witness.wi
+            );
+
+            let is_cubic_residue = fi.pow(cofactor_cubic.to_u64_digits()) == ark_bn254::Fq12::ONE;
+            assert_eq!(witness.wi == ark_bn254::Fq12::ONE, is_cubic_residue);
+            wis.push(witness.wi);
+        }
+        // wi is 1, w' and w'^2 respectively, for some 27-th root w'.
+        assert!(wis.contains(&ark_bn254::Fq12::ONE));
+        assert_ne!(wis[0], wis[1]);
+        assert_ne!(wis[1], wis[2]);
+        assert_ne!(wis[0], wis[2]);
+    }
+
+    #[test]
+    fn test_finding_c_with_one() {
+        // f = 1 is the output of empty miller loop, which is cubic residue.
+        let witness = LambdaResidues::finding_c(ark_bn254::Fq12::ONE).unwrap();
+        assert_eq!(witness.wi, ark_bn254::Fq12::ONE);
+        assert_eq!(
+            witness.c.pow(params::LAMBDA.deref().to_u64_digits()),
+            ark_bn254::Fq12::ONE
+        );
+    }
+
+    #[test]
+    fn test_bn254_residue_params() {
+        // the derived parameters agree with the ones built from the generated constants of BN254
+        let bn254 = ark_bn254::Config::residue_params();
+        let derived = BnResidueParams::<ark_bn254::Config>::with_w(params::W).unwrap();
+        assert_eq!(bn254.lambda, derived.lambda);
+        assert_eq!(bn254.h, derived.h);
+        assert_eq!((bn254.h_bad, bn254.d), (27, 3));
+        assert_eq!((bn254.h_bad, bn254.d), (derived.h_bad, derived.d));
+        assert_eq!(bn254.w, derived.w);
+        assert_eq!(bn254.wi, derived.wi);
+        assert_eq!(bn254.h_dash, derived.h_dash);
+        assert_eq!(bn254.h_dash_inv, derived.h_dash_inv);
+        assert_eq!(bn254.lambda_inv, derived.lambda_inv);
+        assert_eq!(bn254.lambda_div_d_inv, derived.lambda_div_d_inv);
+        assert_eq!(bn254.e, derived.e);
+        assert_eq!(bn254.hk_inv, derived.hk_inv);
+
+        // a sampled w differs from W, but generates the same 27-th roots of unity
+        let sampled = BnResidueParams::<ark_bn254::Config>::new().unwrap();
+        assert_eq!(sampled.h_dash, bn254.h_dash);
+        assert_eq!(sampled.w.pow([27_u64]), ark_bn254::Fq12::ONE);
+        assert!((0..27).any(|i| params::W.pow([i]) == sampled.w));
+
+        test_residue_params_on::<ark_bn254::Config>(27, 3);
+        test_finding_c_for_each_wi_on::<ark_bn254::Config>(3);
+    }
+
+    #[test]
+    fn test_wi() {
+        let bn254 = ark_bn254::Config::residue_params();
+        for (i, wi) in params::WI.iter().enumerate() {
+            assert_eq!(bn254.wi(i as u8), Ok(*wi));
+            assert_eq!(bn254.wi_index(wi), Some(i as u8));
+        }
+        assert_eq!(bn254.wi(3), Err(PairingError::InvalidWitness));
+        assert_eq!(bn254.wi_index(&params::W.pow([3_u64])), None);
+    }
 }
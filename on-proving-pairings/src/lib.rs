@@ -1,8 +1,18 @@
 #![doc = include_str!("../../README.md")]
 #![allow(non_snake_case)]
+pub mod bls12;
+#[cfg(test)]
+mod bn_p254;
+pub mod bw6;
+pub mod error;
 pub mod lambda_residues;
 pub mod params;
+pub mod params_gen;
+pub mod proof;
 pub mod prover;
+pub mod residue_proof;
 pub mod setup;
+#[cfg(test)]
+mod toy_bn;
 mod utils;
 pub mod verifier;
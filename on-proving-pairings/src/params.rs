@@ -1,115 +1,447 @@
 #![allow(non_snake_case)]
 
 /// Ref: 4.3.1 Parameters of [On Proving Pairings](https://eprint.iacr.org/2024/640.pdf)
-use crate::params;
-use ark_bn254::{Fq, Fq2};
-use ark_ff::Field;
+use crate::lambda_residues::BnResidueParams;
+use ark_ec::bn::BnConfig;
 use num_bigint::BigUint;
-use num_traits::{FromPrimitive, Num, One, Pow};
+use num_traits::{FromPrimitive, One, Pow, Zero};
 use once_cell::sync::Lazy;
 use std::clone::Clone;
-use std::ops::{Add, Deref, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
-use std::str::FromStr;
+
+// Generated by `bn_params` from x = 4965661367192848881, where h_bad = 27 and d = 3.
+use ark_bn254::{Fq12, Fq2, Fq6};
+use ark_ff::{Field, MontFp};
 
 // constant modulus of Fq
 pub const MODULUS_STR: &str = "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
-pub const MODULUS: Lazy<BigUint> = Lazy::new(|| BigUint::from_str_radix(MODULUS_STR, 16).unwrap());
-
-// const X: &'static [u64] = &[4965661367192848881]. See more on: Config::X
-pub static X: Lazy<BigUint> = Lazy::new(|| BigUint::from_i128(4965661367192848881).unwrap());
-pub static R: Lazy<BigUint> = Lazy::new(|| {
-    BigUint::from_str(
-        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
-    )
-    .unwrap()
-});
-pub static R_INV: Lazy<BigUint> = Lazy::new(|| {
-    BigUint::from_str(
-        "495819184011867778744231927046742333492451180917315223017345540833046880485481720031136878341141903241966521818658471092566752321606779256340158678675679238405722886654128392203338228575623261160538734808887996935946888297414610216445334190959815200956855428635568184508263913274453942864817234480763055154719338281461936129150171789463489422401982681230261920147923652438266934726901346095892093443898852488218812468761027620988447655860644584419583586883569984588067403598284748297179498734419889699245081714359110559679136004228878808158639412436468707589339209058958785568729925402190575720856279605832146553573981587948304340677613460685405477047119496887534881410757668344088436651291444274840864486870663164657544390995506448087189408281061890434467956047582679858345583941396130713046072603335601764495918026585155498301896749919393",
-    )
-        .unwrap()
-});
-
-pub const H: Lazy<BigUint> = Lazy::new(||
+
+// |x|, see more on `Config::X`
+pub const X: &[u64] = &[4965661367192848881];
+pub const X_IS_NEGATIVE: bool = false;
+
+// The parameters are precomputed as little-endian u64 limbs, and checked by the tests.
+// p, the same as `Fq::MODULUS`
+pub const MODULUS_LIMBS: [u64; 4] = [
+    4332616871279656263,
+    10917124144477883021,
+    13281191951274694749,
+    3486998266802970665,
+];
+
+// r
+pub const R_LIMBS: [u64; 4] = [
+    4891460686036598785,
+    2896914383306846353,
+    13281191951274694749,
+    3486998266802970665,
+];
+
+// r^{-1} mod h
+pub const R_INV_LIMBS: [u64; 44] = [
+    11608900242075462817,
+    11957288855914354513,
+    3116787106681700788,
+    4056127484362567420,
+    8118514525076557193,
+    69240188769302606,
+    9133271292293368251,
+    2039292154691536571,
+    14287587744049093151,
+    3554310640973855491,
+    12261137717040107045,
+    2251832533098411731,
+    7336272344092593252,
+    14765170906362374105,
+    15300949932261141727,
+    14106183133602261101,
+    12737401427063335353,
+    171177352230438000,
+    13049041856653590179,
+    18305798765839010998,
+    16104228988624323963,
+    1739542763929064922,
+    5601136942292264253,
+    15574900073644834590,
+    18274434499964322805,
+    17916135654498903305,
+    18322269162143131602,
+    4007644745573738693,
+    13947846285796705775,
+    16368008805899974858,
+    472327754523454952,
+    5885354980194466143,
+    2546607790591198545,
+    2891428736624824818,
+    17184353915837753597,
+    14744553112369412456,
+    9373784872161472250,
+    16200138677860926851,
+    11959944834529249218,
+    9547417553780286347,
+    5489829204756096954,
+    17399753614172681083,
+    8755379657557813582,
+    182295210734,
+];
+
 // h = (p^12 - 1) / r
-MODULUS.deref().pow(12).sub(BigUint::one()).div(R.clone()));
+pub const H_LIMBS: [u64; 44] = [
+    9698021743855595808,
+    4658111487712502692,
+    9475478476403788475,
+    3895898136474990872,
+    13897688294677189338,
+    13692288569157338976,
+    15521367811043670911,
+    13992850401411864641,
+    6609620042336070051,
+    9167096575297741460,
+    3006977925533509404,
+    13799376210358020352,
+    7213564696215919148,
+    12108970941387295838,
+    14800348210198864764,
+    5333217130945090002,
+    17191330154042290397,
+    7728981312468502308,
+    13479501571575199621,
+    4632319730382815766,
+    6183408236485651195,
+    2344383644319854215,
+    9541507823907846048,
+    5234407941292577173,
+    16529975799043132950,
+    12351756573642376427,
+    9426269327694809050,
+    7379223421642392714,
+    5792417538046516732,
+    9162926010144764881,
+    8644015420738790472,
+    18370314904686314414,
+    17975984934167627464,
+    8719923968173632426,
+    6379321585415610019,
+    1402842025008175984,
+    888598832447275954,
+    4522688638863333623,
+    15776483769708984925,
+    16276864970431725248,
+    7646110518075161570,
+    9524343276244152831,
+    2377296829910687932,
+    203128949104,
+];
 
-pub const D: Lazy<BigUint> = Lazy::new(||
-// d = gcd(m, h) = 3
-BigUint::from_i8(3).unwrap());
-pub const M: Lazy<BigUint> = Lazy::new(||
 // m = λ / r
-LAMBDA.deref().div(R.clone()));
-pub const M_DASH: Lazy<BigUint> = Lazy::new(||
-// m' = m/d
-M.clone().div(D.clone()));
-
-// e = 6X + 2;
-pub static E: Lazy<BigUint> = Lazy::new(|| {
-    let x6 = BigUint::from_i8(6).unwrap().mul(X.clone());
-    // 6x + 2
-    x6 + BigUint::from_i8(2).unwrap()
-});
-
-// optimal lambda in miller loop, lambda
-pub const LAMBDA: Lazy<BigUint> = Lazy::new(|| {
-    // lambdax = 6X + 2 + p - p^2 + p^3
-    let p = MODULUS.clone();
-    let p_pow2 = p.clone().pow(2_u32);
-    let p_pow3 = p.clone().pow(3_u32);
-    let x6 = BigUint::from_i8(6).unwrap().mul(X.deref());
-    let two = BigUint::from_i8(2).unwrap();
-    p_pow3.sub(p_pow2).add(p).add(two).add(x6)
-});
-
-pub static BETA: Lazy<Fq2> = Lazy::new(|| Fq2::new(Fq::from(9), Fq::ONE));
-
-pub static BETA_PI_1: Lazy<Vec<Fq2>> = Lazy::new(|| {
-    let mut res = vec![];
-    for i in 1..6 {
-        // exp = i * ((module-1)/6)
-        let mut t = params::MODULUS.clone();
-        t.sub_assign(BigUint::one());
-        t.div_assign(BigUint::from_i32(6).unwrap());
-        t.mul_assign(BigUint::from_i32(i).unwrap());
-        let exp = t;
-        let pi = BETA.pow(exp.to_u64_digits());
-        res.push(pi);
-    }
-    res
-});
-
-pub static BETA_PI_2: Lazy<Vec<Fq2>> = Lazy::new(|| {
-    let mut res = vec![];
-    for i in 1..6 {
-        // exp = i * ((module^2 -1)/6)
-        let mut t = params::MODULUS.clone();
-        t = t.pow(2_u32);
-        t.sub_assign(BigUint::one());
-        t.div_assign(BigUint::from_i32(6).unwrap());
-        t.mul_assign(BigUint::from_i32(i).unwrap());
-        let exp = t;
-        let pi = BETA.pow(exp.to_u64_digits());
-        res.push(pi);
-    }
-    res
-});
-
-pub static BETA_PI_3: Lazy<Vec<Fq2>> = Lazy::new(|| {
-    let mut res = vec![];
-    for i in 1..6 {
-        // exp = i * ((module^3 -1)/6)
-        let mut t = MODULUS.clone();
-        t = t.pow(3_u32);
-        t.sub_assign(BigUint::one());
-        t.div_assign(BigUint::from_i32(6).unwrap());
-        t.mul_assign(BigUint::from_i32(i).unwrap());
-        let exp = t;
-        let pi = BETA.pow(exp.to_u64_digits());
-        res.push(pi);
+pub const M_LIMBS: [u64; 8] = [
+    10390265939901680725,
+    12671979262699790289,
+    13692122934406044596,
+    14656974190111049063,
+    11829071987463877983,
+    14201444059625953049,
+    6456595594210759196,
+    659149217016364956,
+];
+
+// m' = m / d
+pub const M_DASH_LIMBS: [u64; 8] = [
+    15761251362440261319,
+    16521822470039631173,
+    4564040978135348198,
+    11034572754606866893,
+    10091938687057809866,
+    17031644069015018760,
+    2152198531403586398,
+    219716405672121652,
+];
+
+// λ = 6x + 2 + p - p^2 + p^3
+pub const LAMBDA_LIMBS: [u64; 12] = [
+    6338883757087263829,
+    18082362518251613329,
+    12769575203635315010,
+    16977236642651234352,
+    8686951213117475924,
+    13764436133107921006,
+    12359884445821460169,
+    15944377745085967733,
+    13997781615140285916,
+    7499777415441582065,
+    3683594101071511471,
+    124599342199167405,
+];
+
+// d = gcd(m, h) = 3
+pub const D: u64 = 3;
+
+// e = 6x + 2
+pub const E_LIMBS: [u64; 2] = [11347224129447541672, 1];
+
+// ξ, which is neither square nor cube in Fp2
+pub const BETA: Fq2 = Fq2::new(MontFp!("9"), MontFp!("1"));
+
+// beta^{i * (p - 1) / 6}, where i = 1,...,5
+pub const BETA_PI_1: [Fq2; 5] = [
+    Fq2::new(
+        MontFp!("8376118865763821496583973867626364092589906065868298776909617916018768340080"),
+        MontFp!("16469823323077808223889137241176536799009286646108169935659301613961712198316"),
+    ),
+    Fq2::new(
+        MontFp!("21575463638280843010398324269430826099269044274347216827212613867836435027261"),
+        MontFp!("10307601595873709700152284273816112264069230130616436755625194854815875713954"),
+    ),
+    Fq2::new(
+        MontFp!("2821565182194536844548159561693502659359617185244120367078079554186484126554"),
+        MontFp!("3505843767911556378687030309984248845540243509899259641013678093033130930403"),
+    ),
+    Fq2::new(
+        MontFp!("2581911344467009335267311115468803099551665605076196740867805258568234346338"),
+        MontFp!("19937756971775647987995932169929341994314640652964949448313374472400716661030"),
+    ),
+    Fq2::new(
+        MontFp!("685108087231508774477564247770172212460312782337200605669322048753928464687"),
+        MontFp!("8447204650696766136447902020341177575205426561248465145919723016860428151883"),
+    ),
+];
+
+// beta^{i * (p^2 - 1) / 6}, where i = 1,...,5
+pub const BETA_PI_2: [Fq2; 5] = [
+    Fq2::new(
+        MontFp!("21888242871839275220042445260109153167277707414472061641714758635765020556617"),
+        MontFp!("0"),
+    ),
+    Fq2::new(
+        MontFp!("21888242871839275220042445260109153167277707414472061641714758635765020556616"),
+        MontFp!("0"),
+    ),
+    Fq2::new(
+        MontFp!("21888242871839275222246405745257275088696311157297823662689037894645226208582"),
+        MontFp!("0"),
+    ),
+    Fq2::new(
+        MontFp!("2203960485148121921418603742825762020974279258880205651966"),
+        MontFp!("0"),
+    ),
+    Fq2::new(
+        MontFp!("2203960485148121921418603742825762020974279258880205651967"),
+        MontFp!("0"),
+    ),
+];
+
+// beta^{i * (p^3 - 1) / 6}, where i = 1,...,5
+pub const BETA_PI_3: [Fq2; 5] = [
+    Fq2::new(
+        MontFp!("11697423496358154304825782922584725312912383441159505038794027105778954184319"),
+        MontFp!("303847389135065887422783454877609941456349188919719272345083954437860409601"),
+    ),
+    Fq2::new(
+        MontFp!("3772000881919853776433695186713858239009073593817195771773381919316419345261"),
+        MontFp!("2236595495967245188281701248203181795121068902605861227855261137820944008926"),
+    ),
+    Fq2::new(
+        MontFp!("19066677689644738377698246183563772429336693972053703295610958340458742082029"),
+        MontFp!("18382399103927718843559375435273026243156067647398564021675359801612095278180"),
+    ),
+    Fq2::new(
+        MontFp!("5324479202449903542726783395506214481928257762400643279780343368557297135718"),
+        MontFp!("16208900380737693084919495127334387981393726419856888799917914180988844123039"),
+    ),
+    Fq2::new(
+        MontFp!("8941241848238582420466759817324047081148088512956452953208002715982955420483"),
+        MontFp!("10338197737521362862238855242243140895517409139741313354160881284257516364953"),
+    ),
+];
+
+// The canonical 27-th root of unity w, which is r-th residue but not λ-th residue.
+// w = z^{(p^12 - 1) / 27}, where z is the generator of Fp12 over Fp6.
+pub const W: Fq12 = Fq12::new(
+    Fq6::new(
+        Fq2::ZERO,
+        Fq2::new(
+            MontFp!("9483667112135124394372960210728142145589475128897916459350428495526310884707"),
+            MontFp!("4534159768373982659291990808346042891252278737770656686799127720849666919525"),
+        ),
+        Fq2::ZERO,
+    ),
+    Fq6::ZERO,
+);
+
+// The allowed scalars wi = w^i, where i = 0,...,2.
+pub const WI: [Fq12; 3] = [
+    Fq12::ONE,
+    W,
+    Fq12::new(
+        Fq6::new(
+            Fq2::ZERO,
+            Fq2::ZERO,
+            Fq2::new(
+                MontFp!(
+                    "19980927575007324824158303482676250543132869272873419411837481938595769097674"
+                ),
+                MontFp!(
+                    "1296461116503196556365099397830741391777269588311624000352671663269058798238"
+                ),
+            ),
+        ),
+        Fq6::ZERO,
+    ),
+];
+
+// Precomputed exponents of `LambdaResidues::finding_c`, as little-endian u64 limbs.
+// Write p^12 - 1 = 27 * t' * r, where gcd(27, t') = 1.
+//
+// t' = (p^12 - 1) / (27 * r), so f^h = 1 iff f^{t'} is 27-th root of unity.
+pub const T_DASH: [u64; 44] = [
+    10607377142574032224,
+    15886415747519710735,
+    10599134799335076396,
+    4926781727868216747,
+    13495771322042913705,
+    15537802155213610167,
+    8090205652661064395,
+    10766444870631671810,
+    6393715433545260540,
+    5122011299676466769,
+    794582296268261519,
+    16908193851088417005,
+    13248211188766570364,
+    7963820583414531985,
+    10113139453412318051,
+    11812143199407683980,
+    636715931631195940,
+    4385535027952807852,
+    3915304516300850285,
+    17935098727660413251,
+    11160419015401425075,
+    6235743715100215509,
+    353389178663253557,
+    3609930678142234639,
+    4028285043244107075,
+    457472465690458386,
+    16063014186037573934,
+    9838282979762078345,
+    2947384956773508266,
+    10587558782066223671,
+    16717254562584001083,
+    9562147698626314274,
+    8864330141432675809,
+    10571151299030255802,
+    17999802500069035260,
+    2784808085920236386,
+    16430016911165796842,
+    850719730095292045,
+    14931781826578132180,
+    9484612515876144305,
+    7798529456625193679,
+    16066646554502364444,
+    4870537235032501823,
+    7523294411,
+];
+
+// the order of W
+pub const H_BAD: u64 = 27;
+
+// t'^{-1} mod 27
+pub const T_DASH_INV_MOD_27: u64 = 11;
+
+// λ^{-1} mod t', which is 0 mod 27 as well.
+pub const LAMBDA_INV: [u64; 44] = [
+    3866036704603588797,
+    11602628878109819725,
+    6148466722407455385,
+    4176201779484363366,
+    4660734080468872308,
+    2180095715177322645,
+    9517495044734243427,
+    14302457381127245067,
+    10895010289194465654,
+    1402158711335929013,
+    16806780986447296034,
+    71109196556535019,
+    10621336657404690745,
+    13212785409558254222,
+    13239218539680351161,
+    6466124357025552925,
+    10382818737739945804,
+    11711663317465038654,
+    10305543592598638338,
+    16008790893843992079,
+    3500339816921374367,
+    9247439668929047715,
+    7335914119128545291,
+    7238141542058741467,
+    3869005967003584825,
+    9573079964285682345,
+    4882435077405606216,
+    1063256494673716843,
+    18006525611756581412,
+    5177179315508875821,
+    1520782040303911906,
+    5730473200761366868,
+    16123311137920132663,
+    11682303474187613755,
+    12344053924860401843,
+    13265861498149371620,
+    2217362164027578934,
+    14635573324586184437,
+    16720786216674081857,
+    11676673482719164943,
+    715657840764903481,
+    14775448580277094918,
+    15379574487275949143,
+    6811016626,
+];
+
+// (λ/3)^{-1} mod 9
+pub const LAMBDA_DIV_3_INV_MOD_9: u64 = 1;
+
+// (h * k)^{-1} mod r, where k = 2x(6x^2 + 3x + 1)
+pub const HK_INV_LIMBS: [u64; 4] = [
+    15622673726220367454,
+    11972989922838837709,
+    3301600580761456682,
+    3151709971918069640,
+];
+// End of the generated parameters.
+
+// BigUint views of the limbs, for the arithmetic over BigUint. Each is converted on the first access.
+pub static MODULUS: Lazy<BigUint> = Lazy::new(|| view(&MODULUS_LIMBS));
+pub static R: Lazy<BigUint> = Lazy::new(|| view(&R_LIMBS));
+pub static H: Lazy<BigUint> = Lazy::new(|| view(&H_LIMBS));
+pub static LAMBDA: Lazy<BigUint> = Lazy::new(|| view(&LAMBDA_LIMBS));
+
+#[cfg(test)]
+static VIEW_INITS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+fn view(limbs: &[u64]) -> BigUint {
+    #[cfg(test)]
+    VIEW_INITS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    limbs_to_biguint(limbs)
+}
+
+// Little-endian u64 limbs to BigUint.
+pub fn limbs_to_biguint(limbs: &[u64]) -> BigUint {
+    limbs
+        .iter()
+        .rev()
+        .fold(BigUint::zero(), |acc, limb| (acc << 64) + limb)
+}
+
+// BN curves with the λ residues parameters.
+pub trait BnResidueConfig: BnConfig {
+    fn residue_params() -> &'static BnResidueParams<Self>;
+}
+
+// BN254 keeps the canonical 27-th root W, so the wi_index of the proofs stays the same.
+// The parameters are built from the generated constants, which the tests check against `with_w(W)`.
+static BN254: Lazy<BnResidueParams<ark_bn254::Config>> = Lazy::new(BnResidueParams::bn254);
+
+impl BnResidueConfig for ark_bn254::Config {
+    fn residue_params() -> &'static BnResidueParams<Self> {
+        &BN254
     }
-    res
-});
+}
 
 pub fn tx(x: BigUint) -> BigUint {
     let p1 = BigUint::from_i8(6).unwrap();
@@ -135,26 +467,52 @@ mod test {
 
     use crate::utils::biguint_to_naf;
 
+    use ark_bn254::Fq;
     use ark_ec::bn::BnConfig;
     use ark_ff::PrimeField;
+    use num_traits::Num;
+    use std::ops::{Deref, Mul};
     use std::str::FromStr;
 
     #[test]
     fn test_equivalently() {
+        let m = limbs_to_biguint(&M_LIMBS);
+        let m_dash = limbs_to_biguint(&M_DASH_LIMBS);
+
         //  λ = 3rm′
-        let actual = M_DASH
+        let actual = m_dash
             .clone()
             .mul(R.clone())
-            .mul(BigUint::from_i8(3).unwrap());
+            .mul(BigUint::from_u64(D).unwrap());
         assert_eq!(actual, LAMBDA.clone());
+        assert_eq!(m, LAMBDA.deref() / R.deref());
 
         // r_inv * r % h == 1
-        let actual = R.clone().mul(R_INV.clone()) % &H.clone();
-        assert_eq!(actual, BigUint::one());
-        let expect = M.clone().mul(M_DASH.clone()) % &H.clone();
+        let actual = R.clone().mul(limbs_to_biguint(&R_INV_LIMBS)) % &H.clone();
         assert_eq!(actual, BigUint::one());
     }
 
+    #[test]
+    fn test_limbs() {
+        let p = BigUint::from_str_radix(MODULUS_STR, 16).unwrap();
+        let x = limbs_to_biguint(X);
+        assert_eq!(MODULUS_LIMBS, Fq::MODULUS.0);
+        assert_eq!(R_LIMBS, ark_bn254::Fr::MODULUS.0);
+        assert_eq!(ark_bn254::Config::X, X);
+        assert_eq!(ark_bn254::Config::X_IS_NEGATIVE, X_IS_NEGATIVE);
+
+        let h = (p.clone().pow(12_u32) - BigUint::one()) / R.deref();
+        assert_eq!(h.to_u64_digits(), H_LIMBS);
+        let lambda = p.clone().pow(3_u32) - p.clone().pow(2_u32) + &p + 6_u32 * &x + 2_u32;
+        assert_eq!(lambda.to_u64_digits(), LAMBDA_LIMBS);
+        let m = &lambda / R.deref();
+        assert_eq!(m.to_u64_digits(), M_LIMBS);
+        assert_eq!((&m / D).to_u64_digits(), M_DASH_LIMBS);
+        let r_inv = R.deref().modinv(&h).unwrap();
+        assert_eq!(r_inv.to_u64_digits(), R_INV_LIMBS);
+        assert_eq!((6_u32 * &x + 2_u32).to_u64_digits(), E_LIMBS);
+    }
+
     #[test]
     fn test_lambda() {
         let actual = LAMBDA.clone();
@@ -180,14 +538,14 @@ mod test {
 
     #[test]
     fn test_e() {
-        let actual = E.clone();
+        let actual = limbs_to_biguint(&E_LIMBS);
         let expect = BigUint::from_str("29793968203157093288").unwrap();
         assert_eq!(actual, expect);
     }
 
     #[test]
     fn test_ATE_LOOP_COUNT() {
-        let digtals_naf = biguint_to_naf(E.clone());
+        let digtals_naf = biguint_to_naf(limbs_to_biguint(&E_LIMBS));
         // NOTE:
         // Even if e_naf is different with ark_bn254::Config::ATE_LOOP_COUNT,
         // they play the same role in pairing_verifier.
@@ -196,6 +554,17 @@ mod test {
         println!("ATE_LOOP_COUNT len: {:?}", ATE_LOOP_COUNT_len);
     }
 
+    #[test]
+    fn test_beta_pi() {
+        for (k, beta_pi) in [(1, BETA_PI_1), (2, BETA_PI_2), (3, BETA_PI_3)] {
+            for i in 1..6 {
+                // exp = i * ((module^k - 1)/6)
+                let exp = (MODULUS.deref().pow(k as u32) - BigUint::one()) / 6_u32 * i as u32;
+                assert_eq!(beta_pi[i - 1], BETA.pow(exp.to_u64_digits()));
+            }
+        }
+    }
+
     #[test]
     fn test_beta() {
         println!("Beta: {:?}", BETA.clone());
@@ -204,4 +573,59 @@ mod test {
         println!("BETA_PI_1_3: {:?}", BETA_PI_1[2].to_string());
         println!("BETA_PI_2_2: {:?}", BETA_PI_2[1].to_string());
     }
+
+    #[test]
+    fn test_w() {
+        let cofactor_cubic =
+            (MODULUS.deref().pow(12_u32) - BigUint::one()) / BigUint::from_i8(3).unwrap();
+
+        // w is 27-th root of unity
+        assert_eq!(W.pow([27_u64]), Fq12::ONE);
+        assert_ne!(W.pow([9_u64]), Fq12::ONE);
+        // w is r-th residue, but it's cubic non-residue
+        assert_eq!(W.pow(H.to_u64_digits()), Fq12::ONE);
+        assert_ne!(W.pow(cofactor_cubic.to_u64_digits()), Fq12::ONE);
+
+        assert_eq!(WI[0], Fq12::ONE);
+        assert_eq!(WI[1], W);
+        assert_eq!(WI[2], W * W);
+    }
+
+    #[test]
+    fn test_finding_c_exponents() {
+        let h = H.clone();
+        let t_dash = &h / 27_u32;
+        assert_eq!(&t_dash * 27_u32, h);
+        assert_eq!(t_dash.to_u64_digits(), T_DASH);
+        assert_eq!((&t_dash * T_DASH_INV_MOD_27) % 27_u32, BigUint::one());
+
+        // CRT: λ^{-1} mod t' and 0 mod 27
+        let inv_27 = BigUint::from(27_u32).modinv(&t_dash).unwrap();
+        let lambda_inv = LAMBDA.deref().modinv(&t_dash).unwrap() * 27_u32 * inv_27 % &h;
+        assert_eq!(lambda_inv.to_u64_digits(), LAMBDA_INV);
+
+        let lambda_div_3 = LAMBDA.deref() / 3_u32;
+        assert_eq!(
+            (lambda_div_3 * LAMBDA_DIV_3_INV_MOD_9) % 9_u32,
+            BigUint::one()
+        );
+    }
+
+    #[test]
+    fn test_params_evaluated_once() {
+        // Each view is converted on the first access only, even under the concurrent accesses.
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        for param in [&MODULUS, &R, &H, &LAMBDA] {
+                            let first: &BigUint = param.deref();
+                            assert!(std::ptr::eq(first, param.deref()));
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(VIEW_INITS.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
 }
@@ -0,0 +1,858 @@
+// Generator of the λ residues parameters of BN curves, from the BN family parameter x.
+//
+// It derives the hand-coded constants of `params` (p, r, λ, m, d, m′, h, s, t, k, ...), the exponents of
+// `BnResidueParams::finding_c`, and the frobenius coefficients over Fp2, checks the conditions of
+// 4.3.1 in [On Proving Pairings](https://eprint.iacr.org/2024/640.pdf), then emits them as Rust source.
+// See `src/bin/bn_params.rs`.
+//
+// The canonical h_bad-th root of unity w = z^{(p^12 - 1) / h_bad} is derived here as well, where z is the
+// generator of Fp12 over Fp6. As z^6 = ξ, any power of z is a multiple of z^i by Fp2, where i ∈ [0, 6).
+use crate::error::ParamsError;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use std::fmt::Write;
+
+// a + b * u over Fp2 = Fp[u] / (u^2 - β), as (a, b)
+pub type Fp2Element = (BigUint, BigUint);
+
+// a0 + a1 * z + ... + a5 * z^5 over Fp12 = Fp2[z] / (z^6 - ξ), as [a0, ..., a5]
+pub type Fp12Element = [Fp2Element; 6];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BnParams {
+    // BN family parameter
+    pub x: BigInt,
+    // p = 36x^4 + 36x^3 + 24x^2 + 6x + 1
+    pub p: BigUint,
+    // r = 36x^4 + 36x^3 + 18x^2 + 6x + 1
+    pub r: BigUint,
+    // e = 6x + 2
+    pub e: BigInt,
+    // λ = 6x + 2 + p - p^2 + p^3
+    pub lambda: BigUint,
+    // m = λ / r
+    pub m: BigUint,
+    // d = gcd(m, h)
+    pub d: BigUint,
+    // m′ = m / d
+    pub m_dash: BigUint,
+    // h = (p^12 - 1) / r
+    pub h: BigUint,
+    // p^12 - 1 = 3^s * t, where gcd(3, t) = 1
+    pub s: u32,
+    pub t: BigUint,
+    // k = (t ± 1) / 3, the exponent of the cubic tonelli-shanks
+    pub k: BigUint,
+    // r^{-1} mod h
+    pub r_inv: BigUint,
+    // m′^{-1} mod r * h, or None if gcd(m′, r * h) != 1, e.g. BN-P254,
+    // which is only required by the m′-th root of the paper, not by `BnResidueParams`.
+    pub m_dash_inv: Option<BigUint>,
+    // h = h_bad * h′, where h_bad collects all the primes of gcd(λ, h), namely the order of w
+    pub h_bad: u64,
+    // h′ = h / h_bad
+    pub h_dash: BigUint,
+    // h′^{-1} mod h_bad
+    pub h_dash_inv: u64,
+    // λ^{-1} mod h′, which is 0 mod h_bad as well
+    pub lambda_inv: BigUint,
+    // (λ/d)^{-1} mod h_bad/d
+    pub lambda_div_d_inv: u64,
+    // (h * k)^{-1} mod r, where k = 2x(6x^2 + 3x + 1) from the hard part of the final exponentiation
+    pub hk_inv: BigUint,
+    // w_cofactor = (p^12 - 1) / h_bad
+    pub w_cofactor: BigUint,
+    // w = z^{w_cofactor}, the canonical generator of the h_bad-th roots of unity
+    pub w: Fp12Element,
+    // the allowed scalars wi = w^i, where i ∈ [0, d)
+    pub wi: Vec<Fp12Element>,
+    // β of Fp2 = Fp[u] / (u^2 - β), a quadratic non-residue of Fp
+    pub fp2_nonresidue: BigInt,
+    // ξ = ξ0 + u, which is neither square nor cube in Fp2
+    pub xi: Fp2Element,
+    // ξ^{(p^i - 1) / 3}, where i = 0,...,5
+    pub frobenius_coeff_fp6_c1: Vec<Fp2Element>,
+    // ξ^{2 * (p^i - 1) / 3}, where i = 0,...,5
+    pub frobenius_coeff_fp6_c2: Vec<Fp2Element>,
+    // ξ^{(p^i - 1) / 6}, where i = 0,...,11
+    pub frobenius_coeff_fp12_c1: Vec<Fp2Element>,
+    // ξ^{i * (p^k - 1) / 6}, where k = 1,2,3 and i = 1,...,5
+    pub beta_pi: [Vec<Fp2Element>; 3],
+    // ξ^{(p - 1) / 3} and ξ^{(p - 1) / 2} of the D-type twist
+    pub twist_mul_by_q_x: Fp2Element,
+    pub twist_mul_by_q_y: Fp2Element,
+}
+
+impl BnParams {
+    // Derive the parameters from x, where ξ = xi + u, or the smallest such one if xi is None.
+    pub fn from_x(x: &BigInt, xi: Option<u64>) -> Result<Self, ParamsError> {
+        let one = BigUint::one();
+        // p(x) and r(x) are positive for any x, by Horner's rule
+        let poly = |coeffs: [i64; 5]| {
+            coeffs
+                .iter()
+                .fold(BigInt::zero(), |acc, &c| acc * x + BigInt::from(c))
+                .to_biguint()
+                .unwrap()
+        };
+        let p = poly([36, 36, 24, 6, 1]);
+        let r = poly([36, 36, 18, 6, 1]);
+        if !is_probable_prime(&p) {
+            return Err(ParamsError::NotPrime("p"));
+        }
+        if !is_probable_prime(&r) {
+            return Err(ParamsError::NotPrime("r"));
+        }
+
+        // λ = 6x + 2 + p - p^2 + p^3 = d * m′ * r
+        let e = x * BigInt::from(6) + BigInt::from(2);
+        let lambda = (BigInt::from(p.pow(3) - p.pow(2) + &p) + &e)
+            .to_biguint()
+            .unwrap();
+        if !(&lambda % &r).is_zero() {
+            return Err(ParamsError::Condition("r | λ"));
+        }
+        let p12 = p.pow(12) - &one;
+        let h = &p12 / &r;
+        let m = &lambda / &r;
+        let d = m.gcd(&h);
+        let m_dash = &m / &d;
+
+        // 4.3.1: gcd(r, h) = 1 for the r-th root, and gcd(m′, r * h) = 1 for the m′-th root.
+        let r_inv = r
+            .modinv(&h)
+            .ok_or(ParamsError::Condition("gcd(r, h) = 1"))?;
+        let m_dash_inv = m_dash.modinv(&(&r * &h));
+
+        // p^12 - 1 = 3^s * t
+        let (mut s, mut t) = (0, p12.clone());
+        while (&t % 3_u32).is_zero() {
+            s += 1;
+            t /= 3_u32;
+        }
+        let k = match (&t % 3_u32).to_u32().unwrap() {
+            1 => (&t - 1_u32) / 3_u32,
+            _ => (&t + 1_u32) / 3_u32,
+        };
+
+        // strip all the primes of gcd(λ, h) from h
+        let mut h_dash = h.clone();
+        loop {
+            let g = h_dash.gcd(&lambda);
+            if g == one {
+                break;
+            }
+            h_dash /= g;
+        }
+        let h_bad = (&h / &h_dash)
+            .to_u64()
+            .ok_or(ParamsError::Condition("h_bad fits in u64"))?;
+        let d_bad = (&lambda % h_bad).to_u64().unwrap().gcd(&h_bad);
+        if BigUint::from(d_bad) != d {
+            return Err(ParamsError::Condition("gcd(λ, h_bad) = d"));
+        }
+        let modinv = |a: &BigUint, m: u64| match m {
+            1 => 0,
+            _ => (a % m).modinv(&BigUint::from(m)).unwrap().to_u64().unwrap(),
+        };
+        let h_dash_inv = modinv(&h_dash, h_bad);
+        let lambda_div_d_inv = modinv(&(&lambda / d_bad), h_bad / d_bad);
+        // CRT: λ^{-1} mod h′, and 0 mod h_bad
+        let h_bad_big = BigUint::from(h_bad);
+        let lambda_inv =
+            lambda.modinv(&h_dash).unwrap() * h_bad_big.modinv(&h_dash).unwrap() * &h_bad_big % &h;
+        // k = 2x(6x^2 + 3x + 1) = 12x^3 + 6x^2 + 2x, which is negative for negative x
+        let hk = (BigInt::from(h.clone())
+            * x
+            * (x * (x * BigInt::from(12) + BigInt::from(6)) + BigInt::from(2)))
+        .mod_floor(&BigInt::from(r.clone()))
+        .to_biguint()
+        .unwrap();
+        let hk_inv = hk
+            .modinv(&r)
+            .ok_or(ParamsError::Condition("gcd(h * k, r) = 1"))?;
+        let w_cofactor = &p12 / h_bad;
+
+        // Fp2 = Fp[u] / (u^2 - β), where β = -1 if p = 3 mod 4
+        let fp2_nonresidue = (1_u32..)
+            .map(|i| -BigInt::from(i))
+            .find(|b| !is_square(&to_fp(b, &p), &p))
+            .unwrap();
+        let fp2 = Fp2 {
+            p: p.clone(),
+            nonresidue: to_fp(&fp2_nonresidue, &p),
+        };
+
+        // ξ = ξ0 + u, which is neither square nor cube in Fp2
+        let is_sextic_nonresidue = |xi: &Fp2Element| {
+            let q = p.pow(2) - &one;
+            fp2.pow(xi, &(&q / 2_u32)) != fp2.one() && fp2.pow(xi, &(&q / 3_u32)) != fp2.one()
+        };
+        let xi = match xi {
+            Some(xi0) => {
+                let xi = (BigUint::from(xi0) % &p, one.clone());
+                if !is_sextic_nonresidue(&xi) {
+                    return Err(ParamsError::Condition("ξ is neither square nor cube"));
+                }
+                xi
+            }
+            None => (1_u64..)
+                .map(|xi0| (BigUint::from(xi0), one.clone()))
+                .find(is_sextic_nonresidue)
+                .unwrap(),
+        };
+
+        // ξ^{j * (p^i - 1) / n}
+        let xi_pow = |i: u32, j: u32, n: u32| fp2.pow(&xi, &((p.pow(i) - &one) * j / n));
+        let frobenius_coeff_fp6_c1 = (0..6).map(|i| xi_pow(i, 1, 3)).collect();
+        let frobenius_coeff_fp6_c2 = (0..6).map(|i| xi_pow(i, 2, 3)).collect();
+        let frobenius_coeff_fp12_c1 = (0..12).map(|i| xi_pow(i, 1, 6)).collect();
+        let beta_pi = [1, 2, 3].map(|k| (1..6).map(|i| xi_pow(k, i, 6)).collect());
+        let twist_mul_by_q_x = xi_pow(1, 1, 3);
+        let twist_mul_by_q_y = xi_pow(1, 1, 2);
+
+        // w = (z + j)^{(p^12 - 1) / h_bad} for the smallest j, such that w is of order h_bad.
+        //  e.g. j = 0 for BN254, namely w = z^t where p^12 - 1 = 27 * t.
+        let fp12 = Fp12 {
+            fp2: &fp2,
+            xi: xi.clone(),
+        };
+        let w = (0_u64..)
+            .map(|j| {
+                let mut z = Fp12Element::default();
+                z[0] = (BigUint::from(j), BigUint::zero());
+                z[1] = fp2.one();
+                fp12.pow(&z, &w_cofactor)
+            })
+            .find(|w| fp12.is_generator(w, h_bad))
+            .unwrap();
+        let wi = (0..d_bad)
+            .scan(fp12.one(), |wi, _| {
+                let res = wi.clone();
+                *wi = fp12.mul(wi, &w);
+                Some(res)
+            })
+            .collect();
+
+        Ok(Self {
+            x: x.clone(),
+            p,
+            r,
+            e,
+            lambda,
+            m,
+            d,
+            m_dash,
+            h,
+            s,
+            t,
+            k,
+            r_inv,
+            m_dash_inv,
+            h_bad,
+            h_dash,
+            h_dash_inv,
+            lambda_inv,
+            lambda_div_d_inv,
+            hk_inv,
+            w_cofactor,
+            w,
+            wi,
+            fp2_nonresidue,
+            xi,
+            frobenius_coeff_fp6_c1,
+            frobenius_coeff_fp6_c2,
+            frobenius_coeff_fp12_c1,
+            beta_pi,
+            twist_mul_by_q_x,
+            twist_mul_by_q_y,
+        })
+    }
+
+    // Emit the parameters as Rust source: the constants of `params`, then the ones of `BnConfig` and
+    // the field configs. `fields` is the module of Fq, Fq2, Fq6 and Fq12, e.g. `ark_bn254`.
+    pub fn to_rust_source(&self, fields: &str) -> String {
+        format!(
+            "{}\n{}",
+            self.params_source(fields),
+            self.config_source(fields)
+        )
+    }
+
+    // Emit the constants in the layout of `params`, which is the generated part of `params.rs` for BN254.
+    pub fn params_source(&self, fields: &str) -> String {
+        let mut src = String::new();
+        let out = &mut src;
+        let (h_bad, d) = (self.h_bad, self.d.to_u64().unwrap());
+        writeln!(
+            out,
+            "// Generated by `bn_params` from x = {}, where h_bad = {} and d = {}.",
+            self.x, h_bad, d
+        )
+        .unwrap();
+        writeln!(out, "use {}::{{Fq12, Fq2, Fq6}};", fields).unwrap();
+        writeln!(out, "use ark_ff::{{Field, MontFp}};").unwrap();
+        writeln!(out).unwrap();
+
+        writeln!(out, "// constant modulus of Fq").unwrap();
+        writeln!(out, "pub const MODULUS_STR: &str = \"{:x}\";", self.p).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "// |x|, see more on `Config::X`").unwrap();
+        let limbs = self.x.magnitude().to_u64_digits();
+        writeln!(out, "pub const X: &[u64] = &{:?};", limbs).unwrap();
+        writeln!(
+            out,
+            "pub const X_IS_NEGATIVE: bool = {};",
+            self.x.sign() == Sign::Minus
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+
+        writeln!(
+            out,
+            "// The parameters are precomputed as little-endian u64 limbs, and checked by the tests."
+        )
+        .unwrap();
+        for (comment, name, value) in [
+            ("p, the same as `Fq::MODULUS`", "MODULUS_LIMBS", &self.p),
+            ("r", "R_LIMBS", &self.r),
+            ("r^{-1} mod h", "R_INV_LIMBS", &self.r_inv),
+            ("h = (p^12 - 1) / r", "H_LIMBS", &self.h),
+            ("m = λ / r", "M_LIMBS", &self.m),
+            ("m' = m / d", "M_DASH_LIMBS", &self.m_dash),
+            ("λ = 6x + 2 + p - p^2 + p^3", "LAMBDA_LIMBS", &self.lambda),
+        ] {
+            writeln!(out, "// {}", comment).unwrap();
+            write_limbs(out, name, value);
+            writeln!(out).unwrap();
+        }
+        writeln!(out, "// d = gcd(m, h) = {}", d).unwrap();
+        writeln!(out, "pub const D: u64 = {};", d).unwrap();
+        writeln!(out).unwrap();
+        if self.x.sign() == Sign::Minus {
+            writeln!(out, "// e = |6x + 2|").unwrap();
+        } else {
+            writeln!(out, "// e = 6x + 2").unwrap();
+        }
+        write_limbs(out, "E_LIMBS", self.e.magnitude());
+        writeln!(out).unwrap();
+
+        writeln!(out, "// ξ, which is neither square nor cube in Fp2").unwrap();
+        writeln!(out, "pub const BETA: Fq2 = {};", fp2_source(&self.xi)).unwrap();
+        writeln!(out).unwrap();
+        for (k, (p_k, beta_pi)) in ["p", "p^2", "p^3"].iter().zip(&self.beta_pi).enumerate() {
+            writeln!(out, "// beta^{{i * ({} - 1) / 6}}, where i = 1,...,5", p_k).unwrap();
+            write_fp2s(out, &format!("BETA_PI_{}", k + 1), beta_pi);
+            writeln!(out).unwrap();
+        }
+
+        writeln!(
+            out,
+            "// The canonical {}-th root of unity w, which is r-th residue but not λ-th residue.",
+            h_bad
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "// w = z^{{(p^12 - 1) / {}}}, where z is the generator of Fp12 over Fp6.",
+            h_bad
+        )
+        .unwrap();
+        writeln!(out, "pub const W: Fq12 = {};", fp12_source(&self.w)).unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "// The allowed scalars wi = w^i, where i = 0,...,{}.",
+            d - 1
+        )
+        .unwrap();
+        writeln!(out, "pub const WI: [Fq12; {}] = [", d).unwrap();
+        for (i, wi) in self.wi.iter().enumerate() {
+            match i {
+                0 => writeln!(out, "    Fq12::ONE,").unwrap(),
+                1 => writeln!(out, "    W,").unwrap(),
+                _ => writeln!(out, "    {},", fp12_source(wi)).unwrap(),
+            }
+        }
+        writeln!(out, "];").unwrap();
+        writeln!(out).unwrap();
+
+        writeln!(
+            out,
+            "// Precomputed exponents of `LambdaResidues::finding_c`, as little-endian u64 limbs."
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "// Write p^12 - 1 = {} * t' * r, where gcd({}, t') = 1.",
+            h_bad, h_bad
+        )
+        .unwrap();
+        writeln!(out, "//").unwrap();
+        writeln!(
+            out,
+            "// t' = (p^12 - 1) / ({} * r), so f^h = 1 iff f^{{t'}} is {}-th root of unity.",
+            h_bad, h_bad
+        )
+        .unwrap();
+        write_limbs(out, "T_DASH", &self.h_dash);
+        writeln!(out).unwrap();
+        writeln!(out, "// the order of W").unwrap();
+        writeln!(out, "pub const H_BAD: u64 = {};", h_bad).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "// t'^{{-1}} mod {}", h_bad).unwrap();
+        writeln!(
+            out,
+            "pub const T_DASH_INV_MOD_{}: u64 = {};",
+            h_bad, self.h_dash_inv
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "// λ^{{-1}} mod t', which is 0 mod {} as well.", h_bad).unwrap();
+        write_limbs(out, "LAMBDA_INV", &self.lambda_inv);
+        writeln!(out).unwrap();
+        writeln!(out, "// (λ/{})^{{-1}} mod {}", d, h_bad / d).unwrap();
+        writeln!(
+            out,
+            "pub const LAMBDA_DIV_{}_INV_MOD_{}: u64 = {};",
+            d,
+            h_bad / d,
+            self.lambda_div_d_inv
+        )
+        .unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "// (h * k)^{{-1}} mod r, where k = 2x(6x^2 + 3x + 1)").unwrap();
+        write_limbs(out, "HK_INV_LIMBS", &self.hk_inv);
+        writeln!(out, "// End of the generated parameters.").unwrap();
+        src
+    }
+
+    // Emit the constants of `BnConfig` and the field configs, e.g. `Fp6Config::FROBENIUS_COEFF_FP6_C1`.
+    pub fn config_source(&self, fields: &str) -> String {
+        let mut src = String::new();
+        let out = &mut src;
+        writeln!(out, "// The constants of `BnConfig` and the field configs.").unwrap();
+        writeln!(out, "use {}::Fq;", fields).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "// Fp2 = Fp[u] / (u^2 - β)").unwrap();
+        writeln!(
+            out,
+            "pub const FP2_NONRESIDUE: Fq = MontFp!(\"{}\");",
+            self.fp2_nonresidue
+        )
+        .unwrap();
+        writeln!(out, "// ξ^{{(p^i - 1) / 3}}").unwrap();
+        write_fp2s(out, "FROBENIUS_COEFF_FP6_C1", &self.frobenius_coeff_fp6_c1);
+        writeln!(out, "// ξ^{{2 * (p^i - 1) / 3}}").unwrap();
+        write_fp2s(out, "FROBENIUS_COEFF_FP6_C2", &self.frobenius_coeff_fp6_c2);
+        writeln!(out, "// ξ^{{(p^i - 1) / 6}}").unwrap();
+        write_fp2s(
+            out,
+            "FROBENIUS_COEFF_FP12_C1",
+            &self.frobenius_coeff_fp12_c1,
+        );
+        writeln!(out, "// ξ^{{(p - 1) / 3}} and ξ^{{(p - 1) / 2}}").unwrap();
+        writeln!(
+            out,
+            "pub const TWIST_MUL_BY_Q_X: Fq2 = {};",
+            fp2_source(&self.twist_mul_by_q_x)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "pub const TWIST_MUL_BY_Q_Y: Fq2 = {};",
+            fp2_source(&self.twist_mul_by_q_y)
+        )
+        .unwrap();
+        src
+    }
+}
+
+fn write_limbs(out: &mut String, name: &str, value: &BigUint) {
+    let limbs = value.to_u64_digits();
+    writeln!(out, "pub const {}: [u64; {}] = [", name, limbs.len()).unwrap();
+    for limb in limbs {
+        writeln!(out, "    {},", limb).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn write_fp2s(out: &mut String, name: &str, values: &[Fp2Element]) {
+    writeln!(out, "pub const {}: [Fq2; {}] = [", name, values.len()).unwrap();
+    for (c0, c1) in values {
+        writeln!(out, "    Fq2::new(").unwrap();
+        writeln!(out, "        MontFp!(\"{}\"),", c0).unwrap();
+        writeln!(out, "        MontFp!(\"{}\"),", c1).unwrap();
+        writeln!(out, "    ),").unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn fp2_source((c0, c1): &Fp2Element) -> String {
+    format!("Fq2::new(MontFp!(\"{}\"), MontFp!(\"{}\"))", c0, c1)
+}
+
+// Fq12::new(a0 + a2 * v + a4 * v^2, a1 + a3 * v + a5 * v^2), where v = z^2.
+fn fp12_source(a: &Fp12Element) -> String {
+    let is_zero = |c: &Fp2Element| c.0.is_zero() && c.1.is_zero();
+    let fp2 = |c: &Fp2Element| {
+        if is_zero(c) {
+            "Fq2::ZERO".to_string()
+        } else {
+            fp2_source(c)
+        }
+    };
+    let fp6 = |cs: [&Fp2Element; 3]| {
+        if cs.iter().all(|c| is_zero(c)) {
+            "Fq6::ZERO".to_string()
+        } else {
+            format!("Fq6::new({}, {}, {})", fp2(cs[0]), fp2(cs[1]), fp2(cs[2]))
+        }
+    };
+    format!(
+        "Fq12::new({}, {})",
+        fp6([&a[0], &a[2], &a[4]]),
+        fp6([&a[1], &a[3], &a[5]])
+    )
+}
+
+// n mod p, for a signed n
+fn to_fp(n: &BigInt, p: &BigUint) -> BigUint {
+    n.mod_floor(&BigInt::from(p.clone())).to_biguint().unwrap()
+}
+
+// Euler's criterion
+fn is_square(a: &BigUint, p: &BigUint) -> bool {
+    a.modpow(&((p - 1_u32) / 2_u32), p).is_one()
+}
+
+// Miller-Rabin over the first 12 primes as bases, which is deterministic below 3.3 * 10^24.
+fn is_probable_prime(n: &BigUint) -> bool {
+    const BASES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < &BigUint::from(2_u32) {
+        return false;
+    }
+    for b in BASES {
+        if (n % b).is_zero() {
+            return n == &BigUint::from(b);
+        }
+    }
+
+    // n - 1 = 2^s * t
+    let n_1 = n - 1_u32;
+    let s = n_1.trailing_zeros().unwrap();
+    let t = &n_1 >> s;
+    BASES.iter().all(|&b| {
+        let mut y = BigUint::from(b).modpow(&t, n);
+        if y.is_one() || y == n_1 {
+            return true;
+        }
+        for _ in 1..s {
+            y = &y * &y % n;
+            if y == n_1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+// Fp2 = Fp[u] / (u^2 - β) over BigUint, as p isn't known at compile time.
+struct Fp2 {
+    p: BigUint,
+    nonresidue: BigUint,
+}
+
+impl Fp2 {
+    fn one(&self) -> Fp2Element {
+        (BigUint::one(), BigUint::zero())
+    }
+
+    fn mul(&self, a: &Fp2Element, b: &Fp2Element) -> Fp2Element {
+        let p = &self.p;
+        let c0 = (&a.0 * &b.0 + &self.nonresidue * (&a.1 * &b.1 % p)) % p;
+        let c1 = (&a.0 * &b.1 + &a.1 * &b.0) % p;
+        (c0, c1)
+    }
+
+    fn pow(&self, a: &Fp2Element, exp: &BigUint) -> Fp2Element {
+        let mut res = self.one();
+        for i in (0..exp.bits()).rev() {
+            res = self.mul(&res, &res);
+            if exp.bit(i) {
+                res = self.mul(&res, a);
+            }
+        }
+        res
+    }
+
+    fn add(&self, a: &Fp2Element, b: &Fp2Element) -> Fp2Element {
+        ((&a.0 + &b.0) % &self.p, (&a.1 + &b.1) % &self.p)
+    }
+}
+
+// Fp12 = Fp2[z] / (z^6 - ξ) over BigUint, which is isomorphic to Fp12 = Fp6[z] / (z^2 - v) of the curve.
+struct Fp12<'a> {
+    fp2: &'a Fp2,
+    xi: Fp2Element,
+}
+
+impl Fp12<'_> {
+    fn one(&self) -> Fp12Element {
+        let mut res = Fp12Element::default();
+        res[0] = self.fp2.one();
+        res
+    }
+
+    fn mul(&self, a: &Fp12Element, b: &Fp12Element) -> Fp12Element {
+        let mut res = Fp12Element::default();
+        for i in 0..6 {
+            for j in 0..6 {
+                let mut c = self.fp2.mul(&a[i], &b[j]);
+                // z^6 = ξ
+                if i + j >= 6 {
+                    c = self.fp2.mul(&c, &self.xi);
+                }
+                res[(i + j) % 6] = self.fp2.add(&res[(i + j) % 6], &c);
+            }
+        }
+        res
+    }
+
+    fn pow(&self, a: &Fp12Element, exp: &BigUint) -> Fp12Element {
+        let mut res = self.one();
+        for i in (0..exp.bits()).rev() {
+            res = self.mul(&res, &res);
+            if exp.bit(i) {
+                res = self.mul(&res, a);
+            }
+        }
+        res
+    }
+
+    // w is of order n, namely w^n = 1 and w^{n/q} != 1 for each prime q | n.
+    fn is_generator(&self, w: &Fp12Element, n: u64) -> bool {
+        let one = self.one();
+        if self.pow(w, &BigUint::from(n)) != one {
+            return false;
+        }
+        let (mut m, mut q) = (n, 2);
+        while m > 1 {
+            if m % q == 0 {
+                if self.pow(w, &BigUint::from(n / q)) == one {
+                    return false;
+                }
+                while m % q == 0 {
+                    m /= q;
+                }
+            }
+            q += 1;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::params;
+    use ark_ec::bn::BnConfig;
+    use ark_ff::fields::{Fp12Config, Fp2Config, Fp6Config};
+    use ark_ff::PrimeField;
+
+    fn to_fp2<P: Fp2Config>(value: &ark_ff::Fp2<P>) -> Fp2Element {
+        (value.c0.into_bigint().into(), value.c1.into_bigint().into())
+    }
+
+    fn to_fp2s<P: Fp2Config>(values: &[ark_ff::Fp2<P>]) -> Vec<Fp2Element> {
+        values.iter().map(to_fp2).collect()
+    }
+
+    // a0 + a1 * z + ... + a5 * z^5, where c0 = a0 + a2 * v + a4 * v^2 and c1 = a1 + a3 * v + a5 * v^2.
+    fn to_fp12<P: Fp12Config>(value: &ark_ff::Fp12<P>) -> Fp12Element {
+        let (c0, c1) = (&value.c0, &value.c1);
+        [c0.c0, c1.c0, c0.c1, c1.c1, c0.c2, c1.c2].map(|c| to_fp2(&c))
+    }
+
+    #[test]
+    fn test_bn254_params() {
+        let x = BigInt::from(4965661367192848881_u64);
+        let bn254 = BnParams::from_x(&x, None).unwrap();
+
+        // the hand-coded constants of `params`
+        assert_eq!(bn254.p, *params::MODULUS);
+        assert_eq!(bn254.r, *params::R);
+        assert_eq!(bn254.lambda, *params::LAMBDA);
+        assert_eq!(bn254.m.to_u64_digits(), params::M_LIMBS);
+        assert_eq!(bn254.d, BigUint::from(params::D));
+        assert_eq!(bn254.m_dash.to_u64_digits(), params::M_DASH_LIMBS);
+        assert_eq!(bn254.h, *params::H);
+        assert_eq!(bn254.r_inv.to_u64_digits(), params::R_INV_LIMBS);
+        assert_eq!(
+            bn254.e,
+            BigInt::from(params::limbs_to_biguint(&params::E_LIMBS))
+        );
+        assert_eq!((bn254.s, bn254.h_bad), (3, params::H_BAD));
+        assert_eq!(bn254.h_dash.to_u64_digits(), params::T_DASH);
+        assert_eq!(bn254.h_dash_inv, params::T_DASH_INV_MOD_27);
+        assert_eq!(bn254.lambda_inv.to_u64_digits(), params::LAMBDA_INV);
+        assert_eq!(bn254.lambda_div_d_inv, params::LAMBDA_DIV_3_INV_MOD_9);
+        assert_eq!(bn254.e.magnitude().to_u64_digits(), params::E_LIMBS);
+        assert_eq!(bn254.hk_inv.to_u64_digits(), params::HK_INV_LIMBS);
+        assert_eq!(bn254.xi, to_fp2(&params::BETA));
+        assert_eq!(bn254.beta_pi[0], to_fp2s(&params::BETA_PI_1));
+        assert_eq!(bn254.beta_pi[1], to_fp2s(&params::BETA_PI_2));
+        assert_eq!(bn254.beta_pi[2], to_fp2s(&params::BETA_PI_3));
+
+        // w = z^{(p^12 - 1) / 27} is the canonical W
+        assert_eq!(
+            &bn254.w_cofactor * BigUint::from(bn254.h_bad),
+            params::MODULUS.pow(12) - 1_u32
+        );
+        assert_eq!(bn254.w, to_fp12(&params::W));
+        assert_eq!(bn254.wi, params::WI.iter().map(to_fp12).collect::<Vec<_>>());
+
+        // the frobenius coefficients of ark_bn254
+        assert_eq!(bn254.fp2_nonresidue, BigInt::from(-1));
+        assert_eq!(
+            bn254.frobenius_coeff_fp6_c1,
+            to_fp2s(ark_bn254::Fq6Config::FROBENIUS_COEFF_FP6_C1)
+        );
+        assert_eq!(
+            bn254.frobenius_coeff_fp6_c2,
+            to_fp2s(ark_bn254::Fq6Config::FROBENIUS_COEFF_FP6_C2)
+        );
+        assert_eq!(
+            bn254.frobenius_coeff_fp12_c1,
+            to_fp2s(ark_bn254::Fq12Config::FROBENIUS_COEFF_FP12_C1)
+        );
+        assert_eq!(
+            bn254.twist_mul_by_q_x,
+            to_fp2(&ark_bn254::Config::TWIST_MUL_BY_Q_X)
+        );
+        assert_eq!(
+            bn254.twist_mul_by_q_y,
+            to_fp2(&ark_bn254::Config::TWIST_MUL_BY_Q_Y)
+        );
+
+        // the other parts of `to_rust_source`
+        let src = bn254.config_source("ark_bn254");
+        assert!(src.contains("pub const FP2_NONRESIDUE: Fq = MontFp!(\"-1\");"));
+        assert!(src.contains("pub const FROBENIUS_COEFF_FP12_C1: [Fq2; 12] = ["));
+    }
+
+    #[test]
+    fn test_bn254_source() {
+        // The generated part of `params.rs` is the output of
+        //  `cargo run -p on-proving-pairings --bin bn_params -- 4965661367192848881 9 ark_bn254`,
+        // up to the formatting of rustfmt.
+        let bn254 = BnParams::from_x(&BigInt::from(4965661367192848881_u64), Some(9)).unwrap();
+        let expected = bn254.params_source("ark_bn254");
+
+        let src = include_str!("params.rs");
+        let start = src.find("// Generated by `bn_params`").unwrap();
+        let end_marker = "// End of the generated parameters.";
+        let end = src.find(end_marker).unwrap() + end_marker.len();
+        let normalize = |s: &str| {
+            s.split_whitespace()
+                .collect::<String>()
+                .replace(",)", ")")
+                .replace(",]", "]")
+        };
+        assert_eq!(normalize(&src[start..end]), normalize(&expected));
+    }
+
+    #[test]
+    fn test_toy_bn_params() {
+        use crate::toy_bn;
+
+        // the smallest ξ is 1 + u, while the toy curve takes 9 + u
+        let toy = BnParams::from_x(&BigInt::from(7), None).unwrap();
+        assert_eq!(toy.xi, (BigUint::one(), BigUint::one()));
+
+        let toy = BnParams::from_x(&BigInt::from(7), Some(9)).unwrap();
+        assert_eq!(toy.p, BigUint::from(100003_u32));
+        assert_eq!(toy.r, BigUint::from(99709_u32));
+        assert_eq!(toy.d, BigUint::from(15_u32));
+        assert_eq!(toy.h_bad, 45);
+        assert_eq!(
+            toy.frobenius_coeff_fp6_c1,
+            to_fp2s(toy_bn::Fq6Config::FROBENIUS_COEFF_FP6_C1)
+        );
+        assert_eq!(
+            toy.frobenius_coeff_fp6_c2,
+            to_fp2s(toy_bn::Fq6Config::FROBENIUS_COEFF_FP6_C2)
+        );
+        assert_eq!(
+            toy.frobenius_coeff_fp12_c1,
+            to_fp2s(toy_bn::Fq12Config::FROBENIUS_COEFF_FP12_C1)
+        );
+        assert_eq!(
+            toy.twist_mul_by_q_x,
+            to_fp2(&toy_bn::Config::TWIST_MUL_BY_Q_X)
+        );
+        assert_eq!(
+            toy.twist_mul_by_q_y,
+            to_fp2(&toy_bn::Config::TWIST_MUL_BY_Q_Y)
+        );
+    }
+
+    #[test]
+    fn test_bn_p254_params() {
+        use crate::bn_p254;
+        use crate::lambda_residues::BnResidueParams;
+
+        let x = -BigInt::from(bn_p254::Config::X[0]);
+        let bn_p254 = BnParams::from_x(&x, None).unwrap();
+        assert_eq!(bn_p254.xi, (BigUint::one(), BigUint::one()));
+        assert_eq!(
+            (bn_p254.h_bad, bn_p254.d.clone()),
+            (405, BigUint::from(15_u32))
+        );
+        // m′ shares 5 with h, so the m′-th root of the paper doesn't apply.
+        assert_eq!(bn_p254.m_dash_inv, None);
+        assert_eq!(
+            bn_p254.frobenius_coeff_fp12_c1,
+            to_fp2s(bn_p254::Fq12Config::FROBENIUS_COEFF_FP12_C1)
+        );
+        assert_eq!(
+            bn_p254.twist_mul_by_q_y,
+            to_fp2(&bn_p254::Config::TWIST_MUL_BY_Q_Y)
+        );
+
+        // the generated w is a generator of the h_bad-th roots of unity over the curve
+        let fp2 = |(c0, c1): &Fp2Element| bn_p254::Fq2::new(c0.clone().into(), c1.clone().into());
+        let fp6 = |a: [&Fp2Element; 3]| bn_p254::Fq6::new(fp2(a[0]), fp2(a[1]), fp2(a[2]));
+        let w = &bn_p254.w;
+        let w = bn_p254::Fq12::new(fp6([&w[0], &w[2], &w[4]]), fp6([&w[1], &w[3], &w[5]]));
+        let params = BnResidueParams::<bn_p254::Config>::with_w(w).unwrap();
+        assert_eq!(
+            params.wi.iter().map(to_fp12).collect::<Vec<_>>(),
+            bn_p254.wi
+        );
+        assert!(bn_p254
+            .to_rust_source("super")
+            .contains("pub const X_IS_NEGATIVE: bool = true;"));
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        // p(2) = 973 = 7 * 139
+        let res = BnParams::from_x(&BigInt::from(2), None);
+        assert_eq!(res, Err(ParamsError::NotPrime("p")));
+
+        // 3 + u is a square, and 7 + u is a cube over the toy curve
+        for xi0 in [3, 7] {
+            let res = BnParams::from_x(&BigInt::from(7), Some(xi0));
+            assert_eq!(
+                res,
+                Err(ParamsError::Condition("ξ is neither square nor cube"))
+            );
+        }
+
+        assert!(is_probable_prime(&params::MODULUS));
+        assert!(is_probable_prime(&params::R));
+        assert!(!is_probable_prime(&params::H));
+    }
+}
@@ -0,0 +1,108 @@
+use crate::error::PairingError;
+use crate::params::BnResidueConfig;
+use ark_bn254::Fq12;
+use ark_ff::fields::fp12_2over3over2::{Fp12, Fp12Config};
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+// Pairing proof, the witness of c^lambda = f * wi over the target field F.
+//
+// It's generated by `PairingProver::prove`, and consumed by `PairingVerifier::verify`.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PairingProof<F: Field = Fq12> {
+    // c
+    pub c: F,
+    // the choice of scalar wi = w^i, where w is the canonical root of `BnResidueParams`.
+    // i ∈ [0, d), e.g. {0,1,2} for BN254 with the canonical 27-th root `params::W`.
+    // It's resolved by the `BnResidueParams` of the curve, see `wi`.
+    pub wi_index: u8,
+    // optional hint: inverse of c, which saves an inversion for verifier.
+    pub c_inv: Option<F>,
+}
+
+impl<T: Fp12Config> PairingProof<Fp12<T>> {
+    // Build the proof from the witness (c, wi) over the curve P,
+    // or `InvalidWitness` if wi isn't one of the d scalars of `P::residue_params()`.
+    pub fn new<P: BnResidueConfig<Fp12Config = T>>(
+        c: Fp12<T>,
+        wi: Fp12<T>,
+        c_inv: Option<Fp12<T>>,
+    ) -> Result<Self, PairingError> {
+        let wi_index = P::residue_params()
+            .wi_index(&wi)
+            .ok_or(PairingError::InvalidWitness)?;
+        Ok(Self { c, wi_index, c_inv })
+    }
+
+    // wi = w^i of the curve P, or `InvalidWitness` if i isn't in [0, d).
+    pub fn wi<P: BnResidueConfig<Fp12Config = T>>(&self) -> Result<Fp12<T>, PairingError> {
+        P::residue_params().wi(self.wi_index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::params;
+    use ark_bn254::Config;
+    use ark_std::UniformRand;
+
+    #[test]
+    fn test_serialize_proof() {
+        let rng = &mut ark_std::test_rng();
+
+        let c = Fq12::rand(rng);
+        for c_inv in [None, c.inverse()] {
+            let proof = PairingProof {
+                c,
+                wi_index: 1,
+                c_inv,
+            };
+
+            let mut bytes = vec![];
+            proof.serialize_compressed(&mut bytes).unwrap();
+            let actual = PairingProof::deserialize_compressed(&bytes[..]).unwrap();
+            assert_eq!(actual, proof);
+        }
+    }
+
+    #[test]
+    fn test_wi() {
+        let c = Fq12::ONE;
+        for (i, wi) in params::WI.iter().enumerate() {
+            let proof = PairingProof {
+                c,
+                wi_index: i as u8,
+                c_inv: None,
+            };
+            assert_eq!(proof.wi::<Config>(), Ok(*wi));
+        }
+
+        let proof = PairingProof {
+            c,
+            wi_index: 3,
+            c_inv: None,
+        };
+        assert_eq!(proof.wi::<Config>(), Err(PairingError::InvalidWitness));
+
+        // build from wi
+        for (i, wi) in params::WI.iter().enumerate() {
+            let proof = PairingProof::new::<Config>(c, *wi, None).unwrap();
+            assert_eq!(proof.wi_index, i as u8);
+        }
+        let rng = &mut ark_std::test_rng();
+        let res = PairingProof::new::<Config>(c, Fq12::rand(rng), None);
+        assert_eq!(res, Err(PairingError::InvalidWitness));
+
+        // the d = 15 scalars of BN-P254 over its own w
+        let bn_p254 = crate::bn_p254::Config::residue_params();
+        assert_eq!(bn_p254.wi.len(), 15);
+        for (i, wi) in bn_p254.wi.iter().enumerate() {
+            let proof =
+                PairingProof::new::<crate::bn_p254::Config>(crate::bn_p254::Fq12::ONE, *wi, None)
+                    .unwrap();
+            assert_eq!(proof.wi_index, i as u8);
+            assert_eq!(proof.wi::<crate::bn_p254::Config>(), Ok(*wi));
+        }
+    }
+}
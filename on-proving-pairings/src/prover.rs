@@ -1,20 +1,98 @@
 /// Generate proof of pairings.
-use crate::params;
-use crate::setup::PairingPVKey;
-use ark_bn254::{Bn254, Fq12, G1Affine, G2Affine};
+use crate::error::PairingError;
+use crate::params::BnResidueConfig;
+use crate::proof::PairingProof;
+use crate::setup::{PairingPVKey, PairingTarget};
 use ark_ec::bn::g2::{mul_by_char, G2HomProjective};
-use ark_ec::bn::{BnConfig, G2Prepared};
+use ark_ec::bn::{Bn, BnConfig, G1Affine, G1Prepared, G2Affine, G2Prepared};
+use ark_ec::pairing::Pairing;
 use ark_ec::AffineRepr;
-use ark_ff::Field;
-use num_traits::One;
+use ark_ff::fields::fp12_2over3over2::Fp12;
+use ark_ff::{Field, Fp2};
+use std::marker::PhantomData;
 use std::ops::Neg;
 
-// Prove pairing and generate proof: final_f.
+// Prove pairing and generate proof: (c, wi).
 //
-// And can verify the proof by check:  c^lambda = final_f * wi, namely c_inv^lambda * final_f * wi = 1
-pub struct PairingProver;
+// And can verify the proof by check:  c^lambda = f * wi, namely c_inv^lambda * f * wi = 1
+pub struct PairingProver<P: BnConfig>(PhantomData<P>);
+
+impl<P: BnResidueConfig> PairingProver<P> {
+    // Prove multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=1`.
+    // Will generate proof: (c, wi), satisfying c^lambda = f * wi
+    //
+    // params:
+    //  @pvk: precompute miller lines for fixed [Q1,...,Qn]
+    //  @eval_points: [P1,...,Pn]. which maps with fixed [Q1,...,Qn]
+    //  @non_fixed_points: [P'1,...,P'm]. which maps with non-fixed [Q'1,...,Q'm]
+    //  @non_fixed_q: [Q'1,...,Q'm]
+    pub fn prove(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+    ) -> Result<PairingProof<Fp12<P::Fp12Config>>, PairingError> {
+        Self::prove_scaled(pvk, eval_points, non_fixed_points, non_fixed_q, Fp12::ONE)
+    }
+
+    // Prove multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=T` for the public target T.
+    // Will generate proof: (c, wi), satisfying c^lambda = f * f_T^{-1} * wi
+    //
+    // params:
+    //  @target: the target T, represented by f_T
+    //  @others: same as `prove`
+    pub fn prove_with_target(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        target: &PairingTarget<P>,
+    ) -> Result<PairingProof<Fp12<P::Fp12Config>>, PairingError> {
+        Self::prove_scaled(
+            pvk,
+            eval_points,
+            non_fixed_points,
+            non_fixed_q,
+            target.f_inv,
+        )
+    }
+
+    // Generate proof (c, wi) of c^lambda = f * scale * wi.
+    fn prove_scaled(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        scale: Fp12<P::Fp12Config>,
+    ) -> Result<PairingProof<Fp12<P::Fp12Config>>, PairingError> {
+        // Each fixed Pi should map with one precompute line, and each non-fixed Pi should map with one Qi
+        if eval_points.len() != pvk.lines.len() || non_fixed_points.len() != non_fixed_q.len() {
+            return Err(PairingError::WrongInputLength);
+        }
+
+        // 1. compute f with miller_loop
+        let a = eval_points
+            .into_iter()
+            .chain(non_fixed_points)
+            .map(G1Prepared::from);
+        let b = pvk
+            .lines
+            .iter()
+            .cloned()
+            .chain(non_fixed_q.into_iter().map(G2Prepared::from));
+        let f = Bn::<P>::multi_miller_loop(a, b).0 * scale;
+
+        // 2. finding_c
+        let witness = P::residue_params().finding_c(f)?;
+        let c_inv = witness.c.inverse().ok_or(PairingError::InvalidWitness)?;
+
+        Ok(PairingProof {
+            c: witness.c,
+            wi_index: witness.wi_index,
+            c_inv: Some(c_inv),
+        })
+    }
 
-impl PairingProver {
     // Prove duality pairing.
     // Will generate proof: final_f to prove `e(P1,Q1)=e(P2,Q2)` <==> `e(P1,Q1)*e(P2,-Q2)=1`
     //
@@ -25,95 +103,20 @@ impl PairingProver {
     //  @eval_points: [P1,P2]. which maps with fixed [Q1,Q2]
     //  @lines: precompute miller lines for Qi. Only support fixed Qi.
     //          Base line: [Q1,Q2]
-    //  @c: c^lambda = f*w^i
-    //  @c_inv: inverse of c
+    //  @proof: pairing proof (c, wi), satisfying c^lambda = f*w^i
     //
     // verify c^lambda = f * wi, namely c_inv^lambda * f * wi = 1
     pub fn prove_dual_pairing(
-        eval_points: Vec<G1Affine>,
-        lines: &Vec<G2Prepared<ark_bn254::Config>>,
-        pvk: &PairingPVKey,
-    ) -> Fq12 {
-        assert_eq!(eval_points.len(), 2, "Should contains 2 G1Affine: P1,P2");
-        assert_eq!(lines.len(), 2, "Only 2 precompute lines for Q1,Q2");
-        assert_eq!(pvk.c * pvk.c_inv, Fq12::ONE, "Check if c·c^−1 = 1");
-
-        // constants: 1/2
-        // TODO: one_half
-        let two_inv = ark_bn254::Fq::one().double().inverse().unwrap();
-
-        // 1. f = c_inv
-        let mut f = pvk.c_inv;
-
-        let mut lines_iters = lines
-            .iter()
-            .map(|item| item.ell_coeffs.iter())
-            .collect::<Vec<_>>();
-
-        // 2. miller loop part, 6x + 2
-        for i in (1..ark_bn254::Config::ATE_LOOP_COUNT.len()).rev() {
-            let bit = ark_bn254::Config::ATE_LOOP_COUNT[i - 1];
-
-            // 2.1 double: f = f * f
-            f = f.square();
-
-            // 2.2 mul c
-            //  f = f * c_inv, if digit == 1
-            //  f = f * c, if digit == -1
-            f = if 1 == bit {
-                f * pvk.c_inv
-            } else if bit == -1 {
-                f * pvk.c
-            } else if bit == 0 {
-                f
-            } else {
-                panic!("bit is not in (-1,1), bit={bit}");
-            };
-
-            // 2.3 accumulate double lines (fixed and non-fixed)
-            // 2.3.1(fixed) f = f * double_line_Q(P). fixed points: P1, P2, P3
-            for (line_i, pi) in lines_iters.iter_mut().zip(eval_points.iter()) {
-                let line_i_0 = line_i.next().unwrap();
-                Bn254::ell(&mut f, line_i_0, pi);
-            }
-
-            if bit == 1 || bit == -1 {
-                // 2.4 accumulate add lines (fixed and non-fixed)
-                // 2.4.1(fixed) f = f * add_line_eval. fixed points: P1, P2, P3
-                for (line_i, pi) in lines_iters.iter_mut().zip(eval_points.iter()) {
-                    let line_i_1 = line_i.next().unwrap();
-                    Bn254::ell(&mut f, line_i_1, pi);
-                }
-            }
+        eval_points: Vec<G1Affine<P>>,
+        lines: &[G2Prepared<P>],
+        proof: &PairingProof<Fp12<P::Fp12Config>>,
+    ) -> Result<Fp12<P::Fp12Config>, PairingError> {
+        // Should contains 2 G1Affine: P1,P2, and only 2 precompute lines for Q1,Q2
+        if eval_points.len() != 2 || lines.len() != 2 {
+            return Err(PairingError::WrongInputLength);
         }
 
-        // 3. f = f * c_inv^p * c^{p^2}
-        f = f
-            * pvk.c_inv.pow(params::MODULUS.to_u64_digits())
-            * pvk.c.pow(params::MODULUS.pow(2).to_u64_digits());
-
-        // 4. scale f: f = f * wi .
-        f = f * pvk.wi;
-
-        // 5 add lines (fixed and non-fixed)
-        // 5.1(fixed) f = f * add_line_eval. fixed points: P1, P2
-        // update f (frobenius map): f = f * add_line_eval([p])
-
-        for (line_i, pi) in lines_iters.iter_mut().zip(eval_points.iter()) {
-            let line_i_1 = line_i.next().unwrap();
-            Bn254::ell(&mut f, line_i_1, pi);
-        }
-
-        // 6. add lines (fixed and non-fixed)
-        // 6.1(fixed) f = f * add_line_eval. fixed points: P1, P2
-        // update f (frobenius map): f = f * add_line_eval([-p^2])
-        for (line_i, pi) in lines_iters.iter_mut().zip(eval_points.iter()) {
-            let line_i_1 = line_i.next().unwrap();
-            Bn254::ell(&mut f, line_i_1, pi);
-        }
-
-        // return final_f
-        f
+        Self::prove_multi_pairing(eval_points, lines, vec![], vec![], proof)
     }
 
     // Prove quadratic pairing.
@@ -128,134 +131,434 @@ impl PairingProver {
     //          Base line: [Q1,Q2,Q3]
     //  @P4: P4
     //  @Q4: non fixed base line.
-    //  @c: c^lambda = f*w^i
-    //  @c_inv: inverse of c
+    //  @proof: pairing proof (c, wi), satisfying c^lambda = f*w^i
     //
     // verify c^lambda = f * wi, namely c_inv^lambda * f * wi = 1
     pub fn prove_quad_pairing(
-        eval_points: Vec<G1Affine>,
-        lines: &Vec<G2Prepared<ark_bn254::Config>>,
-        P4: G1Affine,
-        Q4: G2Affine,
-        pvk: &PairingPVKey,
-    ) -> Fq12 {
-        assert_eq!(eval_points.len(), 3, "Should contains 3 G1Affine: P1,P2,P3");
-        assert_eq!(lines.len(), 3, "Only 3 precompute lines for Q1,Q2,Q3");
-        assert_eq!(pvk.c * pvk.c_inv, Fq12::ONE, "Check if c·c^−1 = 1");
-
-        let mut T4 = G2HomProjective::<ark_bn254::Config> {
-            x: Q4.x,
-            y: Q4.y,
-            z: ark_bn254::Fq2::one(),
+        eval_points: Vec<G1Affine<P>>,
+        lines: &[G2Prepared<P>],
+        P4: G1Affine<P>,
+        Q4: G2Affine<P>,
+        proof: &PairingProof<Fp12<P::Fp12Config>>,
+    ) -> Result<Fp12<P::Fp12Config>, PairingError> {
+        // Should contains 3 G1Affine: P1,P2,P3, and only 3 precompute lines for Q1,Q2,Q3
+        if eval_points.len() != 3 || lines.len() != 3 {
+            return Err(PairingError::WrongInputLength);
+        }
+
+        Self::prove_multi_pairing(eval_points, lines, vec![P4], vec![Q4], proof)
+    }
+
+    // Prove multi pairing.
+    // Will generate proof: final_f to prove `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=1`
+    //
+    // All pairs share one merged miller loop, where (Q1,...,Qn) are fixed,
+    // and (Q'1,...,Q'm) are non-fixed. m is arbitrary, as each Q'j takes its own projective accumulator.
+    // The pairs with the point at infinity are skipped, as e(O,Q) = e(P,O) = 1.
+    //
+    // params:
+    //  @eval_points: [P1,...,Pn]. which maps with fixed [Q1,...,Qn]
+    //  @lines: precompute miller lines for Qi. Only support fixed Qi.
+    //          Base line: [Q1,...,Qn]
+    //  @non_fixed_points: [P'1,...,P'm]. which maps with non-fixed [Q'1,...,Q'm]
+    //  @non_fixed_q: [Q'1,...,Q'm]. non fixed base lines, computed along the loop.
+    //  @proof: pairing proof (c, wi), satisfying c^lambda = f*w^i
+    //
+    // verify c^lambda = f * wi, namely c_inv^lambda * f * wi = 1
+    pub fn prove_multi_pairing(
+        eval_points: Vec<G1Affine<P>>,
+        lines: &[G2Prepared<P>],
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        proof: &PairingProof<Fp12<P::Fp12Config>>,
+    ) -> Result<Fp12<P::Fp12Config>, PairingError> {
+        // Check if c·c^−1 = 1
+        let c_inv = match proof.c_inv {
+            Some(c_inv) => c_inv,
+            None => proof.c.inverse().ok_or(PairingError::InvalidWitness)?,
         };
+        if proof.c * c_inv != Fp12::ONE {
+            return Err(PairingError::InvalidWitness);
+        }
+        // wi = w^i
+        let wi = P::residue_params().wi(proof.wi_index)?;
+
+        Self::merged_miller_loop(
+            &eval_points,
+            lines,
+            &non_fixed_points,
+            &non_fixed_q,
+            proof.c,
+            c_inv,
+            wi,
+        )
+    }
+
+    // Merged miller loop with c_inv/c folded in, which is shared by the prover and the verifier.
+    //
+    // Return final_f = c_inv^{6x+2+p-p^2} * f * wi, which equals to c^{p^3} iff c^lambda = f * wi.
+    //
+    // For negative x, the loop runs over |6x+2| and f is conjugated afterwards, same as `Bn::multi_miller_loop`.
+    // So the loop takes conj(c) rather than c_inv, since conj(conj(c)^{|6x+2|}) = c_inv^{6x+2}.
+    pub(crate) fn merged_miller_loop(
+        eval_points: &[G1Affine<P>],
+        lines: &[G2Prepared<P>],
+        non_fixed_points: &[G1Affine<P>],
+        non_fixed_q: &[G2Affine<P>],
+        c: Fp12<P::Fp12Config>,
+        c_inv: Fp12<P::Fp12Config>,
+        wi: Fp12<P::Fp12Config>,
+    ) -> Result<Fp12<P::Fp12Config>, PairingError> {
+        // Each fixed Pi should map with one precompute line, and each non-fixed Pi should map with one Qi
+        if eval_points.len() != lines.len() || non_fixed_points.len() != non_fixed_q.len() {
+            return Err(PairingError::WrongInputLength);
+        }
+
+        // Skip the pairs with the point at infinity, as e(O,Q) = e(P,O) = 1, same as `Bn::multi_miller_loop`.
+        let (eval_points, lines): (Vec<_>, Vec<_>) = eval_points
+            .iter()
+            .zip(lines)
+            .filter(|(p, q)| !p.is_zero() && !q.is_zero())
+            .unzip();
+        let (non_fixed_points, non_fixed_q): (Vec<_>, Vec<_>) = non_fixed_points
+            .iter()
+            .zip(non_fixed_q)
+            .filter(|(p, q)| !p.is_zero() && !q.is_zero())
+            .map(|(p, q)| (*p, *q))
+            .unzip();
+
+        // Ti: the accumulators for non-fixed Qi, one for each.
+        //  Each Ti takes its own doubling, ±Qi addition, phi(Qi) and -phi^2(Qi) addition steps,
+        //  while all the lines are evaluated into the same f.
+        let mut T = non_fixed_q
+            .iter()
+            .map(|q| G2HomProjective::<P> {
+                x: q.x,
+                y: q.y,
+                z: Fp2::ONE,
+            })
+            .collect::<Vec<_>>();
 
         // constants: 1/2
-        let two_inv = ark_bn254::Fq::one().double().inverse().unwrap();
+        let two_inv = P::Fp::ONE.double().inverse().unwrap();
+
+        // (u, u_inv) = (c_inv, c), or (conj(c), conj(c_inv)) for negative x
+        let (u, u_inv) = if P::X_IS_NEGATIVE {
+            let (mut u, mut u_inv) = (c, c_inv);
+            u.conjugate_in_place();
+            u_inv.conjugate_in_place();
+            (u, u_inv)
+        } else {
+            (c_inv, c)
+        };
 
-        // 1. f = c_inv
-        let mut f = pvk.c_inv;
+        // 1. f = u
+        let mut f = u;
 
         let mut lines_iters = lines
             .iter()
             .map(|item| item.ell_coeffs.iter())
             .collect::<Vec<_>>();
 
-        // 2. miller loop part, 6x + 2
-        for i in (1..ark_bn254::Config::ATE_LOOP_COUNT.len()).rev() {
-            let bit = ark_bn254::Config::ATE_LOOP_COUNT[i - 1];
+        // 2. miller loop part, |6x + 2|
+        for i in (1..P::ATE_LOOP_COUNT.len()).rev() {
+            let bit = P::ATE_LOOP_COUNT[i - 1];
 
             // 2.1 double: f = f * f
             f = f.square();
 
             // 2.2 mul c
-            //  f = f * c_inv, if digit == 1
-            //  f = f * c, if digit == -1
-            f = if 1 == bit {
-                f * pvk.c_inv
-            } else if bit == -1 {
-                f * pvk.c
-            } else if bit == 0 {
-                f
-            } else {
-                panic!("bit is not in (-1,1), bit={bit}");
+            //  f = f * u, if digit == 1
+            //  f = f * u_inv, if digit == -1
+            f = match bit {
+                1 => f * u,
+                -1 => f * u_inv,
+                _ => f,
             };
 
             // 2.3 accumulate double lines (fixed and non-fixed)
-            // 2.3.1(fixed) f = f * double_line_Q(P). fixed points: P1, P2, P3
+            // 2.3.1(fixed) f = f * double_line_Q(P). fixed points: P1,...,Pn
             for (line_i, pi) in lines_iters.iter_mut().zip(eval_points.iter()) {
-                let line_i_0 = line_i.next().unwrap();
-                Bn254::ell(&mut f, line_i_0, pi);
+                let line_i_0 = line_i.next().ok_or(PairingError::WrongInputLength)?;
+                Bn::<P>::ell(&mut f, line_i_0, pi);
             }
 
-            // 2.3.2(non-fixed) double line with T4 (projective coordinates)
-            let double_line = T4.double_in_place(&two_inv); // TODO: check if the param is 1/2
-
-            // 2.3.3(non-fixed) evaluation double_line. non-fixed points: P4
-            Bn254::ell(&mut f, &double_line, &P4);
+            // 2.3.2(non-fixed) double line with Ti (projective coordinates),
+            //      then evaluation double_line. non-fixed points: P'1,...,P'm
+            for (Ti, pi) in T.iter_mut().zip(non_fixed_points.iter()) {
+                let double_line = Ti.double_in_place(&two_inv);
+                Bn::<P>::ell(&mut f, &double_line, pi);
+            }
 
             if bit == 1 || bit == -1 {
                 // 2.4 accumulate add lines (fixed and non-fixed)
-                // 2.4.1(fixed) f = f * add_line_eval. fixed points: P1, P2, P3
+                // 2.4.1(fixed) f = f * add_line_eval. fixed points: P1,...,Pn
                 for (line_i, pi) in lines_iters.iter_mut().zip(eval_points.iter()) {
-                    let line_i_1 = line_i.next().unwrap();
-                    Bn254::ell(&mut f, line_i_1, pi);
+                    let line_i_1 = line_i.next().ok_or(PairingError::WrongInputLength)?;
+                    Bn::<P>::ell(&mut f, line_i_1, pi);
+                }
+
+                // 2.4.2(non-fixed) add line with Ti and ±Qi (projective coordinates),
+                //      then evaluation add_line. non-fixed points: P'1,...,P'm
+                for ((Ti, qi), pi) in T
+                    .iter_mut()
+                    .zip(non_fixed_q.iter())
+                    .zip(non_fixed_points.iter())
+                {
+                    let add_line = if bit == 1 {
+                        Ti.add_in_place(qi)
+                    } else {
+                        // }else if bit == -1 {
+                        Ti.add_in_place(&qi.neg())
+                    };
+                    Bn::<P>::ell(&mut f, &add_line, pi);
                 }
-                // 2.4.2(non-fixed) double line with T4 (projective coordinates)
-                let add_line = if bit == 1 {
-                    T4.add_in_place(&Q4)
-                } else {
-                    // }else if bit == -1 {
-                    T4.add_in_place(&Q4.neg())
-                };
-
-                // 2.4.3(non-fixed) evaluation double_line. non-fixed points: P4
-                Bn254::ell(&mut f, &add_line, &P4);
             }
         }
 
-        // 3. f = f * c_inv^p * c^{p^2}
-        f = f
-            * pvk.c_inv.pow(params::MODULUS.to_u64_digits())
-            * pvk.c.pow(params::MODULUS.pow(2).to_u64_digits());
+        // f = conj(f), and Ti = -Ti for negative x, namely Ti = [6x+2]Qi.
+        if P::X_IS_NEGATIVE {
+            f.conjugate_in_place();
+            for Ti in T.iter_mut() {
+                Ti.y.neg_in_place();
+            }
+        }
+
+        // 3. f = f * c_inv^p * c^{p^2}, where raising to p^i is the i-th frobenius map.
+        f *= c_inv.frobenius_map(1) * c.frobenius_map(2);
 
         // 4. scale f: f = f * wi .
-        f = f * pvk.wi;
+        f *= wi;
 
         // 5. one-time frobenius map on fixed and non-fixed lines
-        // 5.1(fixed) f = f * add_line_eval. fixed points: P1, P2, P3
+        // 5.1(fixed) f = f * add_line_eval. fixed points: P1,...,Pn
         for (line_i, pi) in lines_iters.iter_mut().zip(eval_points.iter()) {
-            let line_i_1 = line_i.next().unwrap();
-            Bn254::ell(&mut f, line_i_1, pi);
+            let line_i_1 = line_i.next().ok_or(PairingError::WrongInputLength)?;
+            Bn::<P>::ell(&mut f, line_i_1, pi);
+        }
+        // 5.2(non-fixed) one-time frobenius map to compute phi(Qi),
+        //     add line with phi(Qi), then evaluation add_line. non-fixed points: P'1,...,P'm
+        let phi_Q = non_fixed_q
+            .iter()
+            .map(|qi| mul_by_char::<P>(*qi))
+            .collect::<Vec<_>>();
+        for ((Ti, phi_Qi), pi) in T.iter_mut().zip(phi_Q.iter()).zip(non_fixed_points.iter()) {
+            let add_line = Ti.add_in_place(phi_Qi);
+            Bn::<P>::ell(&mut f, &add_line, pi);
         }
-        // 5.2(non-fixed) one-time frobenius map to compute phi_Q
-        //     compute phi(Q) with Q4
-        let phi_Q = mul_by_char::<ark_bn254::Config>(Q4.clone());
-
-        // 5.3(non-fixed) add line with phi_Q
-        let add_line = T4.add_in_place(&phi_Q);
-
-        // 5.4(non-fixed) evaluation add_lin. non-fixed points: P4
-        Bn254::ell(&mut f, &add_line, &P4);
 
         // 6. two-times frobenius map on fixed and non-fixed lines
-        // 6.1(fixed) f = f * add_line_eval. fixed points: P1, P2, P3
+        // 6.1(fixed) f = f * add_line_eval. fixed points: P1,...,Pn
         for (line_i, pi) in lines_iters.iter_mut().zip(eval_points.iter()) {
-            let line_i_1 = line_i.next().unwrap();
-            Bn254::ell(&mut f, line_i_1, pi);
+            let line_i_1 = line_i.next().ok_or(PairingError::WrongInputLength)?;
+            Bn::<P>::ell(&mut f, line_i_1, pi);
         }
-        // 6.2 two-time frobenius map to compute phi_Q
-        //     compute phi_Q_2 with phi_Q
+        // 6.2(non-fixed) two-time frobenius map to compute -phi^2(Qi) with phi(Qi),
+        //     add line with -phi^2(Qi), then evaluation add_line. non-fixed points: P'1,...,P'm
         // mul_by_char: used to q's frob...map.
-        let mut phi_Q_2 = mul_by_char::<ark_bn254::Config>(phi_Q.clone());
-        phi_Q_2.y.neg_in_place();
-
-        // 6.3 add line with phi_Q_2
-        let add_line = T4.add_in_place(&phi_Q_2);
+        for ((Ti, phi_Qi), pi) in T.iter_mut().zip(phi_Q).zip(non_fixed_points.iter()) {
+            let mut phi_Qi_2 = mul_by_char::<P>(phi_Qi);
+            phi_Qi_2.y.neg_in_place();
 
-        // 6.4 evaluation add_lin. non-fixed points: P4
-        Bn254::ell(&mut f, &add_line, &P4);
+            let add_line = Ti.add_in_place(&phi_Qi_2);
+            Bn::<P>::ell(&mut f, &add_line, pi);
+        }
 
         // return final_f
-        f
+        Ok(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::params;
+    use crate::utils::sample_pairing_equation;
+
+    #[test]
+    fn test_prove_multi_pairing_with_fixed_q() {
+        let (P, Q) = sample_pairing_equation(5, &mut ark_std::test_rng());
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q);
+        let proof = PairingProver::prove(&pvk, P.clone(), vec![], vec![]).unwrap();
+
+        // final_f = c^{p^3}
+        let final_f =
+            PairingProver::prove_multi_pairing(P, &pvk.lines, vec![], vec![], &proof).unwrap();
+        let p_pow3 = params::MODULUS.pow(3_u32);
+        assert_eq!(final_f, proof.c.pow(p_pow3.to_u64_digits()));
+    }
+
+    #[test]
+    fn test_prove_multi_pairing_with_non_fixed_q() {
+        let (P, Q) = sample_pairing_equation(4, &mut ark_std::test_rng());
+
+        // (Q1,Q2) are fixed, and (Q3,Q4) are non-fixed.
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..2].to_vec());
+        let proof =
+            PairingProver::prove(&pvk, P[0..2].to_vec(), P[2..4].to_vec(), Q[2..4].to_vec())
+                .unwrap();
+
+        // final_f = c^{p^3}
+        let final_f = PairingProver::prove_multi_pairing(
+            P[0..2].to_vec(),
+            &pvk.lines,
+            P[2..4].to_vec(),
+            Q[2..4].to_vec(),
+            &proof,
+        )
+        .unwrap();
+        let p_pow3 = params::MODULUS.pow(3_u32);
+        assert_eq!(final_f, proof.c.pow(p_pow3.to_u64_digits()));
+    }
+
+    #[test]
+    fn test_prove_multi_pairing_with_many_non_fixed_q() {
+        let p_pow3 = params::MODULUS.pow(3_u32);
+        let (P, Q) = sample_pairing_equation(7, &mut ark_std::test_rng());
+
+        // all the Qi are non-fixed, or (Q1,Q2) are fixed and (Q3,...,Q7) are non-fixed.
+        for n in [0, 2] {
+            let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..n].to_vec());
+            let proof =
+                PairingProver::prove(&pvk, P[0..n].to_vec(), P[n..].to_vec(), Q[n..].to_vec())
+                    .unwrap();
+            let final_f = PairingProver::prove_multi_pairing(
+                P[0..n].to_vec(),
+                &pvk.lines,
+                P[n..].to_vec(),
+                Q[n..].to_vec(),
+                &proof,
+            )
+            .unwrap();
+            assert_eq!(final_f, proof.c.pow(p_pow3.to_u64_digits()));
+        }
+
+        // the same non-fixed Q twice, namely e(P,Q) * e(-P,Q) = 1, takes two accumulators.
+        let (P1, Q1) = (P[0], Q[0]);
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(Vec::<ark_bn254::G2Affine>::new());
+        let proof = PairingProver::prove(&pvk, vec![], vec![P1, -P1], vec![Q1, Q1]).unwrap();
+        let final_f = PairingProver::prove_multi_pairing(
+            vec![],
+            &pvk.lines,
+            vec![P1, -P1],
+            vec![Q1, Q1],
+            &proof,
+        )
+        .unwrap();
+        assert_eq!(final_f, proof.c.pow(p_pow3.to_u64_digits()));
+    }
+
+    #[test]
+    fn test_prove_multi_pairing_with_infinity() {
+        let p_pow3 = params::MODULUS.pow(3_u32);
+        let (mut P, mut Q) = sample_pairing_equation(4, &mut ark_std::test_rng());
+
+        // e(O,Q) = e(P,O) = 1, for both the fixed and non-fixed pairs.
+        let (O1, O2) = (
+            G1Affine::<ark_bn254::Config>::zero(),
+            G2Affine::<ark_bn254::Config>::zero(),
+        );
+        P.insert(1, O1);
+        Q.insert(1, Q[0]);
+        P.insert(2, P[0]);
+        Q.insert(2, O2);
+        P.extend([O1, P[0]]);
+        Q.extend([Q[0], O2]);
+
+        // (Q1,...,Q4) are fixed, and (Q5,...,Q8) are non-fixed.
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..4].to_vec());
+        let proof =
+            PairingProver::prove(&pvk, P[0..4].to_vec(), P[4..].to_vec(), Q[4..].to_vec()).unwrap();
+        let final_f = PairingProver::prove_multi_pairing(
+            P[0..4].to_vec(),
+            &pvk.lines,
+            P[4..].to_vec(),
+            Q[4..].to_vec(),
+            &proof,
+        )
+        .unwrap();
+        assert_eq!(final_f, proof.c.pow(p_pow3.to_u64_digits()));
+
+        // only the pairs with infinity, namely f = 1.
+        assert!(P[6].is_zero() && Q[2].is_zero());
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(vec![Q[2]]);
+        let proof = PairingProver::prove(&pvk, vec![P[2]], vec![P[6]], vec![Q[6]]).unwrap();
+        let final_f = PairingProver::prove_multi_pairing(
+            vec![P[2]],
+            &pvk.lines,
+            vec![P[6]],
+            vec![Q[6]],
+            &proof,
+        )
+        .unwrap();
+        assert_eq!(final_f, proof.c.pow(p_pow3.to_u64_digits()));
+    }
+
+    #[test]
+    fn test_prove_quad_pairing() {
+        let (P, Q) = sample_pairing_equation(4, &mut ark_std::test_rng());
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..3].to_vec());
+        let proof = PairingProver::prove(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]]).unwrap();
+
+        // final_f = c^{p^3}
+        let final_f =
+            PairingProver::prove_quad_pairing(P[0..3].to_vec(), &pvk.lines, P[3], Q[3], &proof)
+                .unwrap();
+        let p_pow3 = params::MODULUS.pow(3_u32);
+        assert_eq!(final_f, proof.c.pow(p_pow3.to_u64_digits()));
+    }
+
+    #[test]
+    fn test_prove_with_wrong_input() {
+        let (P, Q) = sample_pairing_equation(4, &mut ark_std::test_rng());
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..3].to_vec());
+
+        // wrong length
+        let res = PairingProver::prove(&pvk, P[0..2].to_vec(), vec![P[3]], vec![Q[3]]);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+        let res = PairingProver::prove(&pvk, P[0..3].to_vec(), vec![P[3]], vec![]);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+
+        // pairing product is not 1
+        let res = PairingProver::prove(&pvk, P[0..3].to_vec(), vec![P[2]], vec![Q[3]]);
+        assert_eq!(res, Err(PairingError::NotRthResidue));
+
+        // wrong lines
+        let proof = PairingProver::prove(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]]).unwrap();
+        let res = PairingProver::prove_quad_pairing(
+            P[0..3].to_vec(),
+            &pvk.lines[0..2],
+            P[3],
+            Q[3],
+            &proof,
+        );
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+        let mut lines = pvk.lines.clone();
+        lines[0].ell_coeffs.pop();
+        let res = PairingProver::prove_quad_pairing(P[0..3].to_vec(), &lines, P[3], Q[3], &proof);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+
+        // wrong hint
+        let wrong_hint = PairingProof {
+            c_inv: Some(proof.c),
+            ..proof.clone()
+        };
+        let res = PairingProver::prove_quad_pairing(
+            P[0..3].to_vec(),
+            &pvk.lines,
+            P[3],
+            Q[3],
+            &wrong_hint,
+        );
+        assert_eq!(res, Err(PairingError::InvalidWitness));
+
+        // wrong wi
+        let wrong_wi = PairingProof {
+            wi_index: 3,
+            ..proof
+        };
+        let res =
+            PairingProver::prove_quad_pairing(P[0..3].to_vec(), &pvk.lines, P[3], Q[3], &wrong_wi);
+        assert_eq!(res, Err(PairingError::InvalidWitness));
     }
 }
@@ -0,0 +1,93 @@
+use ark_ff::Field;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+// Pairing proof, the witness of c^lambda = f * wi over the target field F, shared by the BLS12 and BW6 curves.
+//
+// Unlike BN254, wi isn't an index, since it absorbs the whole part of f in the subgroup of order h_bad.
+// So the verifier checks wi^{h_bad} = 1 instead.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PairingProof<F: Field> {
+    // c
+    pub c: F,
+    // wi, where wi^{h_bad} = 1
+    pub wi: F,
+    // optional hint: inverse of c, which saves an inversion for verifier.
+    pub c_inv: Option<F>,
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::error::PairingError;
+    use ark_bls12_381::Fq12;
+    use ark_std::UniformRand;
+
+    // The checks of the verifier shared by the curves, where verify(P4, proof) verifies
+    // e(P1,Q1)*e(P2,Q2)*e(P3,Q3)*e(P4,Q4) = 1, and the proof is honest for P4 = honest_p4.
+    pub(crate) fn check_verify<F: Field, G: Copy>(
+        verify: impl Fn(G, &PairingProof<F>) -> Result<bool, PairingError>,
+        proof: &PairingProof<F>,
+        honest_p4: G,
+        wrong_p4: G,
+    ) {
+        // 1. with and without hint
+        assert_eq!(verify(honest_p4, proof), Ok(true));
+        let mut no_hint = proof.clone();
+        no_hint.c_inv = None;
+        assert_eq!(verify(honest_p4, &no_hint), Ok(true));
+
+        // 2. wrong hint
+        let mut wrong_hint = proof.clone();
+        wrong_hint.c_inv = Some(proof.c);
+        assert_eq!(
+            verify(honest_p4, &wrong_hint),
+            Err(PairingError::InvalidWitness)
+        );
+
+        // 3. wrong statement
+        assert_eq!(verify(wrong_p4, proof), Ok(false));
+    }
+
+    // The verifier rejects wi out of the subgroup of order h_bad, where wrong_f is the miller loop
+    // of the wrong statement with P4 = wrong_p4.
+    pub(crate) fn check_forged_wi<F: Field, G: Copy, R: rand::Rng>(
+        verify: impl Fn(G, &PairingProof<F>) -> Result<bool, PairingError>,
+        proof: &PairingProof<F>,
+        (honest_p4, wrong_p4): (G, G),
+        wrong_f: F,
+        lambda: &[u64],
+        rng: &mut R,
+    ) {
+        // 1. random wi
+        let mut random_wi = proof.clone();
+        random_wi.wi = F::rand(rng);
+        assert_eq!(
+            verify(honest_p4, &random_wi),
+            Err(PairingError::InvalidWitness)
+        );
+
+        // 2. forge wi = c^lambda / f to absorb the discrepancy of a wrong statement.
+        let c = F::rand(rng);
+        let forged = PairingProof {
+            c,
+            wi: c.pow(lambda) * wrong_f.inverse().unwrap(),
+            c_inv: None,
+        };
+        assert_eq!(verify(wrong_p4, &forged), Err(PairingError::InvalidWitness));
+    }
+
+    #[test]
+    fn test_serialize_proof() {
+        let rng = &mut ark_std::test_rng();
+
+        let (c, wi) = (Fq12::rand(rng), Fq12::rand(rng));
+        for c_inv in [None, c.inverse()] {
+            let proof = PairingProof { c, wi, c_inv };
+
+            let mut bytes = vec![];
+            proof.serialize_compressed(&mut bytes).unwrap();
+            let actual = PairingProof::deserialize_compressed(&bytes[..]).unwrap();
+            assert_eq!(actual, proof);
+        }
+    }
+}
@@ -1,37 +1,105 @@
-use crate::lambda_residues::LambdaResidues;
-use ark_bn254::{Bn254, Fq12};
-use ark_ec::bn::{G1Prepared, G2Prepared};
-use ark_ec::pairing::Pairing;
-use ark_ff::Field;
-
-// To prove and verify the pairing, need precompute the lambda residues over BN curve.
-pub struct PairingPVKey {
-    // f, then base line
-    pub f: Fq12,
-    // c
-    pub c: Fq12,
-    pub wi: Fq12,
-    pub c_inv: Fq12,
+use crate::error::PairingError;
+use crate::params::BnResidueConfig;
+use ark_ec::bn::{Bn, BnConfig, G2Prepared};
+use ark_ec::pairing::PairingOutput;
+use ark_ec::CurveConfig;
+use ark_ff::fields::fp12_2over3over2::Fp12;
+use ark_ff::{Field, PrimeField};
+
+// To prove and verify the pairing, need precompute the miller lines for the fixed Qi.
+//
+// The key is statement-independent, it can be reused by all the pairing proofs over the same fixed Qi.
+pub struct PairingPVKey<P: BnConfig> {
+    // precompute miller lines for fixed Qi.
+    //  Base line: [Q1,...,Qn]
+    pub lines: Vec<G2Prepared<P>>,
+}
+
+impl<P: BnConfig> PairingPVKey<P> {
+    pub fn setup(fixed_q: impl IntoIterator<Item = impl Into<G2Prepared<P>>>) -> Self {
+        // precompute lines for fixed Qi
+        let lines = fixed_q.into_iter().map(|q| q.into()).collect();
+
+        Self { lines }
+    }
 }
 
-impl PairingPVKey {
-    pub fn setup(
-        a: impl IntoIterator<Item = impl Into<G1Prepared<ark_bn254::Config>>>,
-        b: impl IntoIterator<Item = impl Into<G2Prepared<ark_bn254::Config>>>,
-    ) -> Self {
-        // compute f, then base line
-        let qap = Bn254::multi_miller_loop(a, b);
-        let f = qap.0;
-
-        // finding_c
-        let witness = LambdaResidues::finding_c(f);
-        let c_inv = witness.c.inverse().unwrap();
-
-        Self {
-            f,
-            c: witness.c,
-            wi: witness.wi,
-            c_inv,
+// The public target T ∈ GT of `e(P1,Q1)*...*e(Pn,Qn) = T`, represented by f_T whose final exponentiation is T,
+// e.g. the miller loop output of (α,β) in Groth16.
+//
+// Write the final exponentiation as f -> f^{h * k}, where h = (p^12 - 1) / r and gcd(k, r) = 1.
+// The product equals T iff (f * f_T^{-1})^{h * k} = 1, namely f * f_T^{-1} is r-th residue,
+// where f is the miller loop output. So f_T^{-1} is folded into the check along with wi, and there's
+// no miller loop for T.
+pub struct PairingTarget<P: BnConfig> {
+    // f_T
+    pub f: Fp12<P::Fp12Config>,
+    // f_T^{-1}
+    pub f_inv: Fp12<P::Fp12Config>,
+}
+
+impl<P: BnResidueConfig> PairingTarget<P> {
+    // Take f_T = T^{(h * k)^{-1} mod r}, as T^r = 1, see `BnResidueParams::hk_inv`.
+    //
+    // return: `InvalidTarget` if T isn't in GT, namely T^r != 1, e.g. zero.
+    pub fn new(target: PairingOutput<Bn<P>>) -> Result<Self, PairingError> {
+        let r = <P::G1Config as CurveConfig>::ScalarField::MODULUS;
+        if target.0.pow(r) != Fp12::ONE {
+            return Err(PairingError::InvalidTarget);
         }
+        Self::from_miller_loop(target.0.pow(&P::residue_params().hk_inv))
+    }
+
+    // Take the miller loop output f_T, e.g. of (α,β), whose final exponentiation is T.
+    //
+    // return: `InvalidTarget` if f_T is zero.
+    pub fn from_miller_loop(f: Fp12<P::Fp12Config>) -> Result<Self, PairingError> {
+        let f_inv = f.inverse().ok_or(PairingError::InvalidTarget)?;
+        Ok(Self { f, f_inv })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::sample_pairing_equation_on;
+    use ark_ec::pairing::{MillerLoopOutput, Pairing};
+
+    fn test_pairing_target_on<P: BnResidueConfig>() {
+        let (P, Q) = sample_pairing_equation_on::<Bn<P>, _>(3, &mut ark_std::test_rng());
+        let target = Bn::<P>::pairing(P[0], Q[0]);
+
+        // the final exponentiation of f_T is T
+        let target_f = PairingTarget::<P>::new(target).unwrap();
+        let res = Bn::<P>::final_exponentiation(MillerLoopOutput(target_f.f)).unwrap();
+        assert_eq!(res, target);
+        assert_eq!(target_f.f * target_f.f_inv, Fp12::ONE);
+
+        // f_T differs from the miller loop output, while both lead to T.
+        let f = Bn::<P>::miller_loop(P[0], Q[0]).0;
+        assert_ne!(target_f.f, f);
+        let target_f = PairingTarget::<P>::from_miller_loop(f).unwrap();
+        let res = Bn::<P>::final_exponentiation(MillerLoopOutput(target_f.f)).unwrap();
+        assert_eq!(res, target);
+
+        // zero, w of order h_bad and T * w aren't in GT
+        let w = P::residue_params().w;
+        for t in [Fp12::ZERO, w, target.0 * w] {
+            assert_eq!(
+                PairingTarget::<P>::new(PairingOutput(t)).err(),
+                Some(PairingError::InvalidTarget)
+            );
+        }
+        assert_eq!(
+            PairingTarget::<P>::from_miller_loop(Fp12::ZERO).err(),
+            Some(PairingError::InvalidTarget)
+        );
+    }
+
+    #[test]
+    fn test_pairing_target() {
+        test_pairing_target_on::<ark_bn254::Config>();
+        test_pairing_target_on::<crate::toy_bn::Config>();
+        test_pairing_target_on::<crate::bn_p254::Config>();
     }
 }
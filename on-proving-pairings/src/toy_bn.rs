@@ -0,0 +1,236 @@
+// A toy BN curve with x = 7, for testing the pipeline over the BN curves other than BN254.
+//
+//  p = 36x^4 + 36x^3 + 24x^2 + 6x + 1 = 100003
+//  r = 36x^4 + 36x^3 + 18x^2 + 6x + 1 = 99709
+//
+// Unlike BN254 (h_bad = 27, d = 3), h_bad = 3^2 * 5 and d = gcd(λ, h_bad) = 15,
+// so there are 15 scalars wi = w^i.
+//
+// E: y^2 = x^3 + 29 over Fp, and the D-type twist E': y^2 = x^3 + 29/ξ over Fp2, where ξ = 9 + u.
+// The frobenius coefficients are the powers of ξ, same as BN254.
+
+// `MontConfig` of ark_ff_macros 0.4 expands to an impl inside a const item.
+#![allow(non_local_definitions)]
+use crate::lambda_residues::BnResidueParams;
+use crate::params::BnResidueConfig;
+use ark_ec::bn::{Bn, BnConfig, TwistType};
+use ark_ec::models::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ec::models::CurveConfig;
+use ark_ff::fields::{
+    Fp12, Fp12Config, Fp2, Fp2Config, Fp6, Fp64, Fp6Config, MontBackend, MontConfig,
+};
+use ark_ff::{Field, MontFp, Zero};
+use once_cell::sync::Lazy;
+
+#[derive(MontConfig)]
+#[modulus = "100003"]
+#[generator = "2"]
+pub struct FqConfig;
+pub type Fq = Fp64<MontBackend<FqConfig, 1>>;
+
+#[derive(MontConfig)]
+#[modulus = "99709"]
+#[generator = "10"]
+pub struct FrConfig;
+pub type Fr = Fp64<MontBackend<FrConfig, 1>>;
+
+pub type Fq2 = Fp2<Fq2Config>;
+pub struct Fq2Config;
+
+impl Fp2Config for Fq2Config {
+    type Fp = Fq;
+
+    // u^2 = -1, as p = 3 mod 4
+    const NONRESIDUE: Fq = MontFp!("-1");
+
+    const FROBENIUS_COEFF_FP2_C1: &'static [Fq] = &[Fq::ONE, MontFp!("-1")];
+}
+
+pub type Fq6 = Fp6<Fq6Config>;
+#[derive(Clone, Copy)]
+pub struct Fq6Config;
+
+impl Fp6Config for Fq6Config {
+    type Fp2Config = Fq2Config;
+
+    // ξ = 9 + u, which is neither square nor cube in Fp2
+    const NONRESIDUE: Fq2 = Fq2::new(MontFp!("9"), Fq::ONE);
+
+    // ξ^{(p^i - 1) / 3}
+    const FROBENIUS_COEFF_FP6_C1: &'static [Fq2] = &[
+        Fq2::new(Fq::ONE, Fq::ZERO),
+        Fq2::new(MontFp!("23780"), MontFp!("23504")),
+        Fq2::new(MontFp!("92882"), Fq::ZERO),
+        Fq2::new(MontFp!("67702"), MontFp!("33038")),
+        Fq2::new(MontFp!("7120"), Fq::ZERO),
+        Fq2::new(MontFp!("8521"), MontFp!("43461")),
+    ];
+
+    // ξ^{2 * (p^i - 1) / 3}
+    const FROBENIUS_COEFF_FP6_C2: &'static [Fq2] = &[
+        Fq2::new(Fq::ONE, Fq::ZERO),
+        Fq2::new(MontFp!("49994"), MontFp!("16706")),
+        Fq2::new(MontFp!("7120"), Fq::ZERO),
+        Fq2::new(MontFp!("46603"), MontFp!("43153")),
+        Fq2::new(MontFp!("92882"), Fq::ZERO),
+        Fq2::new(MontFp!("3406"), MontFp!("40144")),
+    ];
+}
+
+pub type Fq12 = Fp12<Fq12Config>;
+#[derive(Clone, Copy)]
+pub struct Fq12Config;
+
+impl Fp12Config for Fq12Config {
+    type Fp6Config = Fq6Config;
+
+    const NONRESIDUE: Fq6 = Fq6::new(Fq2::ZERO, Fq2::ONE, Fq2::ZERO);
+
+    // ξ^{(p^i - 1) / 6}
+    const FROBENIUS_COEFF_FP12_C1: &'static [Fq2] = &[
+        Fq2::new(Fq::ONE, Fq::ZERO),
+        Fq2::new(MontFp!("20470"), MontFp!("79661")),
+        Fq2::new(MontFp!("92883"), Fq::ZERO),
+        Fq2::new(MontFp!("57974"), MontFp!("30696")),
+        Fq2::new(MontFp!("92882"), Fq::ZERO),
+        Fq2::new(MontFp!("37504"), MontFp!("51038")),
+        Fq2::new(MontFp!("100002"), Fq::ZERO),
+        Fq2::new(MontFp!("79533"), MontFp!("20342")),
+        Fq2::new(MontFp!("7120"), Fq::ZERO),
+        Fq2::new(MontFp!("42029"), MontFp!("69307")),
+        Fq2::new(MontFp!("7121"), Fq::ZERO),
+        Fq2::new(MontFp!("62499"), MontFp!("48965")),
+    ];
+}
+
+pub struct G1Config;
+
+impl CurveConfig for G1Config {
+    type BaseField = Fq;
+    type ScalarField = Fr;
+
+    // #E(Fp) = p + 1 - (6x^2 + 1) = r
+    const COFACTOR: &'static [u64] = &[1];
+    const COFACTOR_INV: Fr = Fr::ONE;
+}
+
+impl SWCurveConfig for G1Config {
+    const COEFF_A: Fq = Fq::ZERO;
+    const COEFF_B: Fq = MontFp!("29");
+    const GENERATOR: Affine<Self> = Affine::new_unchecked(MontFp!("3"), MontFp!("17170"));
+
+    fn mul_by_a(_: Self::BaseField) -> Self::BaseField {
+        Self::BaseField::zero()
+    }
+}
+
+pub struct G2Config;
+
+impl CurveConfig for G2Config {
+    type BaseField = Fq2;
+    type ScalarField = Fr;
+
+    // #E'(Fp2) = r * (2p - r)
+    const COFACTOR: &'static [u64] = &[100297];
+    const COFACTOR_INV: Fr = MontFp!("56807");
+}
+
+impl SWCurveConfig for G2Config {
+    const COEFF_A: Fq2 = Fq2::ZERO;
+    // 29 / ξ
+    const COEFF_B: Fq2 = Fq2::new(MontFp!("32931"), MontFp!("96344"));
+    const GENERATOR: Affine<Self> = Affine::new_unchecked(
+        Fq2::new(MontFp!("77522"), MontFp!("54460")),
+        Fq2::new(MontFp!("64862"), MontFp!("95400")),
+    );
+
+    fn mul_by_a(_: Self::BaseField) -> Self::BaseField {
+        Self::BaseField::zero()
+    }
+}
+
+pub struct Config;
+
+impl BnConfig for Config {
+    const X: &'static [u64] = &[7];
+    const X_IS_NEGATIVE: bool = false;
+    // 6x + 2 = 44
+    const ATE_LOOP_COUNT: &'static [i8] = &[0, 0, 1, 1, 0, 1];
+
+    // ξ^{(p - 1) / 3} and ξ^{(p - 1) / 2}
+    const TWIST_MUL_BY_Q_X: Fq2 = Fq2::new(MontFp!("23780"), MontFp!("23504"));
+    const TWIST_MUL_BY_Q_Y: Fq2 = Fq2::new(MontFp!("66024"), MontFp!("94201"));
+    const TWIST_TYPE: TwistType = TwistType::D;
+    type Fp = Fq;
+    type Fp2Config = Fq2Config;
+    type Fp6Config = Fq6Config;
+    type Fp12Config = Fq12Config;
+    type G1Config = G1Config;
+    type G2Config = G2Config;
+}
+
+pub type ToyBn = Bn<Config>;
+
+static TOY_BN: Lazy<BnResidueParams<Config>> = Lazy::new(|| BnResidueParams::new().unwrap());
+
+impl BnResidueConfig for Config {
+    fn residue_params() -> &'static BnResidueParams<Self> {
+        &TOY_BN
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lambda_residues::test::{test_finding_c_for_each_wi_on, test_residue_params_on};
+    use crate::utils::sample_pairing_equation_on;
+    use crate::verifier::test::test_prove_and_verify_on;
+    use ark_ec::pairing::Pairing;
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::PrimeField;
+    use ark_std::UniformRand;
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_toy_bn() {
+        let rng = &mut ark_std::test_rng();
+
+        // the generators are in the r-torsion
+        let r: BigUint = Fr::MODULUS.into();
+        let g1 = Affine::<G1Config>::generator();
+        let g2 = Affine::<G2Config>::generator();
+        assert!(g1.is_on_curve() && g2.is_on_curve());
+        assert!(g1.mul_bigint(r.to_u64_digits()).is_zero());
+        assert!(g2.mul_bigint(r.to_u64_digits()).is_zero());
+        assert!(Affine::<G2Config>::rand(rng).is_in_correct_subgroup_assuming_on_curve());
+
+        // the frobenius map is raising to p
+        let f = Fq12::rand(rng);
+        assert_eq!(f.frobenius_map(1), f.pow(Fq::MODULUS));
+
+        // the pairing is bilinear and non-degenerate
+        let (P, Q) = sample_pairing_equation_on::<ToyBn, _>(3, rng);
+        assert!(ToyBn::multi_pairing(P, Q).is_zero());
+        assert!(!ToyBn::pairing(g1, g2).is_zero());
+        let a = Fr::rand(rng);
+        assert_eq!(
+            ToyBn::pairing((g1 * a).into_affine(), g2),
+            ToyBn::pairing(g1, (g2 * a).into_affine())
+        );
+    }
+
+    #[test]
+    fn test_residue_params() {
+        test_residue_params_on::<Config>(45, 15);
+    }
+
+    #[test]
+    fn test_finding_c_for_each_wi() {
+        test_finding_c_for_each_wi_on::<Config>(15);
+    }
+
+    #[test]
+    fn test_prove_and_verify() {
+        test_prove_and_verify_on::<Config>(15);
+    }
+}
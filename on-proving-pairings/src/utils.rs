@@ -1,3 +1,4 @@
+use ark_ff::{BitIteratorBE, Field};
 use num_bigint::BigUint;
 use num_traits::ToPrimitive;
 
@@ -21,6 +22,79 @@ fn to_naf(mut x: i128) -> Vec<i8> {
     return z;
 }
 
+// Sliding window exponentiation, where exp is little-endian u64 limbs.
+//
+// Compared with square-and-multiply, it takes about bits/(WINDOW+1) multiplications
+// rather than bits/2, at the cost of 2^(WINDOW-1) precomputed odd powers.
+pub(crate) fn pow_windowed<F: Field>(base: F, exp: &[u64]) -> F {
+    const WINDOW: usize = 5;
+
+    // odd powers: base, base^3, ..., base^{2^WINDOW - 1}
+    let base_sq = base.square();
+    let mut table = vec![base];
+    for i in 1..(1 << (WINDOW - 1)) {
+        table.push(table[i - 1] * base_sq);
+    }
+
+    let bits = BitIteratorBE::without_leading_zeros(exp).collect::<Vec<_>>();
+    let mut res = F::one();
+    let mut i = 0;
+    while i < bits.len() {
+        if !bits[i] {
+            res.square_in_place();
+            i += 1;
+            continue;
+        }
+        // the longest window bits[i..j] ending with bit 1
+        let mut j = usize::min(i + WINDOW, bits.len());
+        while !bits[j - 1] {
+            j -= 1;
+        }
+        let mut value = 0;
+        for &bit in &bits[i..j] {
+            res.square_in_place();
+            value = (value << 1) | bit as usize;
+        }
+        res *= table[value >> 1];
+        i = j;
+    }
+    res
+}
+
+// sample n pairs satisfying `e(P1,Q1)*...*e(Pn,Qn)=1`
+#[cfg(test)]
+pub(crate) fn sample_pairing_equation<R: rand::Rng>(
+    n: usize,
+    rng: &mut R,
+) -> (Vec<ark_bn254::G1Affine>, Vec<ark_bn254::G2Affine>) {
+    sample_pairing_equation_on::<ark_bn254::Bn254, R>(n, rng)
+}
+
+// sample n pairs satisfying `e(P1,Q1)*...*e(Pn,Qn)=1` over any pairing-friendly curve.
+#[cfg(test)]
+pub(crate) fn sample_pairing_equation_on<E: ark_ec::pairing::Pairing, R: rand::Rng>(
+    n: usize,
+    rng: &mut R,
+) -> (Vec<E::G1Affine>, Vec<E::G2Affine>) {
+    use ark_ec::{CurveGroup, Group};
+    use ark_std::UniformRand;
+    use std::ops::Mul;
+
+    let mut P = vec![];
+    let mut Q = vec![];
+    let mut acc = E::ScalarField::from(0_u64);
+    for _ in 0..n - 1 {
+        let (a, b) = (E::ScalarField::rand(rng), E::ScalarField::rand(rng));
+        acc += a * b;
+        P.push(E::G1::generator().mul(a).into_affine());
+        Q.push(E::G2::generator().mul(b).into_affine());
+    }
+    P.push(E::G1::generator().mul(-acc).into_affine());
+    Q.push(E::G2::generator().into_affine());
+
+    (P, Q)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -30,7 +104,7 @@ mod test {
     use ark_ec::bn::g2::mul_by_char;
     use ark_std::UniformRand;
     use std::ops::Mul;
-    use std::ops::{Deref, Neg};
+    use std::ops::Neg;
 
     #[test]
     fn test_biguint_naf() {
@@ -39,8 +113,9 @@ mod test {
         expect.remove(0);
         println!("res: {:?}", expect);
 
-        println!("E :{:?}", params::E.deref());
-        let mut actual = crate::utils::biguint_to_naf(params::E.clone());
+        let e = params::limbs_to_biguint(&params::E_LIMBS);
+        println!("E :{:?}", e);
+        let mut actual = crate::utils::biguint_to_naf(e);
         actual.reverse();
         actual.remove(0);
         println!("res: {:?}", actual);
@@ -107,4 +182,33 @@ mod test {
         assert_eq!(actual_2.y, Q4.y);
         assert_eq!(actual_2, phi_Q_2);
     }
+
+    #[test]
+    fn test_frobenius_map() {
+        let rng = &mut ark_std::test_rng();
+        let c = ark_bn254::Fq12::rand(rng);
+
+        // c^{p^i} is the i-th frobenius map of c.
+        for i in 1..4 {
+            let p_pow_i = params::MODULUS.pow(i as u32);
+            assert_eq!(c.frobenius_map(i), c.pow(p_pow_i.to_u64_digits()));
+        }
+    }
+
+    #[test]
+    fn test_pow_windowed() {
+        let rng = &mut ark_std::test_rng();
+        let f = ark_bn254::Fq12::rand(rng);
+
+        assert_eq!(pow_windowed(f, &[]), ark_bn254::Fq12::ONE);
+        assert_eq!(pow_windowed(f, &[0, 0]), ark_bn254::Fq12::ONE);
+        for exp in [1_u64, 2, 31, 32, 33, 0b1000_0001, u64::MAX] {
+            assert_eq!(pow_windowed(f, &[exp]), f.pow([exp]));
+        }
+        assert_eq!(pow_windowed(f, &params::T_DASH), f.pow(params::T_DASH));
+        assert_eq!(
+            pow_windowed(f, &params::LAMBDA_INV),
+            f.pow(params::LAMBDA_INV)
+        );
+    }
 }
@@ -1,29 +1,576 @@
-use crate::params;
-use crate::setup::PairingPVKey;
-use ark_bn254::Fq12;
+use crate::error::PairingError;
+use crate::params::BnResidueConfig;
+use crate::proof::PairingProof;
+use crate::prover::PairingProver;
+use crate::setup::{PairingPVKey, PairingTarget};
+use ark_ec::bn::{BnConfig, G1Affine, G2Affine, G2Prepared};
+use ark_ff::fields::fp12_2over3over2::Fp12;
 use ark_ff::Field;
+use std::marker::PhantomData;
 
-pub struct PairingVerifier;
+pub struct PairingVerifier<P: BnConfig>(PhantomData<P>);
 
-impl PairingVerifier {
-    pub fn verify(pvk: &PairingPVKey, final_f: Fq12) -> bool {
-        let p_pow3 = params::MODULUS.pow(3_u32);
-        let lambda = params::LAMBDA.clone();
-        let (exp, sign) = if lambda > p_pow3 {
-            (lambda - p_pow3, true)
-        } else {
-            (p_pow3 - lambda, false)
+impl<P: BnResidueConfig> PairingVerifier<P> {
+    // Verify multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=1` with the pairing proof.
+    //
+    // params:
+    //  @pvk: precompute miller lines for fixed [Q1,...,Qn]
+    //  @eval_points: [P1,...,Pn]. which maps with fixed [Q1,...,Qn]
+    //  @non_fixed_points: [P'1,...,P'm]. which maps with non-fixed [Q'1,...,Q'm]
+    //  @non_fixed_q: [Q'1,...,Q'm]
+    //  @proof: pairing proof (c, i), satisfying c^lambda = f*w^i
+    //
+    // return: Ok(false) if the check fails, or the error if the inputs or proof are malformed.
+    pub fn verify(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        proof: &PairingProof<Fp12<P::Fp12Config>>,
+    ) -> Result<bool, PairingError> {
+        Self::verify_scaled(
+            pvk,
+            eval_points,
+            non_fixed_points,
+            non_fixed_q,
+            proof,
+            Fp12::ONE,
+        )
+    }
+
+    // Verify multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=T` for the public target T.
+    //
+    // f_T^{-1} is folded into the check along with wi, namely c_inv^lambda * f * f_T^{-1} * wi = 1,
+    // so there's no extra miller loop for T.
+    //
+    // params:
+    //  @target: the target T, represented by f_T
+    //  @others: same as `verify`
+    pub fn verify_with_target(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        target: &PairingTarget<P>,
+        proof: &PairingProof<Fp12<P::Fp12Config>>,
+    ) -> Result<bool, PairingError> {
+        Self::verify_scaled(
+            pvk,
+            eval_points,
+            non_fixed_points,
+            non_fixed_q,
+            proof,
+            target.f_inv,
+        )
+    }
+
+    // Check c_inv^lambda * f * scale * wi = 1.
+    fn verify_scaled(
+        pvk: &PairingPVKey<P>,
+        eval_points: Vec<G1Affine<P>>,
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        proof: &PairingProof<Fp12<P::Fp12Config>>,
+        scale: Fp12<P::Fp12Config>,
+    ) -> Result<bool, PairingError> {
+        // check the hint c_inv if provided, otherwise compute it.
+        let c_inv = match proof.c_inv {
+            Some(c_inv) if proof.c * c_inv == Fp12::ONE => c_inv,
+            Some(_) => return Err(PairingError::InvalidWitness),
+            None => proof.c.inverse().ok_or(PairingError::InvalidWitness)?,
         };
+        // wi = w^i, reject the proof if i isn't in [0, d).
+        let wi = P::residue_params().wi(proof.wi_index)?;
+
+        Self::check(
+            &eval_points,
+            &pvk.lines,
+            &non_fixed_points,
+            &non_fixed_q,
+            proof.c,
+            c_inv,
+            // scale is multiplied along with wi in the merged miller loop
+            wi * scale,
+        )
+    }
+
+    // Verify multi pairing `e(P1,Q1)*...*e(Pn,Qn)*e(P'1,Q'1)*...*e(P'm,Q'm)=1`
+    // without the miller loop output f.
+    //
+    // Only public data and the witness (c, wi) are used:
+    //  1. redo the merged miller loop with c_inv/c folded in, namely final_f = c_inv^{6x+2+p-p^2} * f * wi
+    //  2. apply the frobenius corrections: final_f * c_inv^{p^3}
+    //  3. check the result equals one, namely c_inv^lambda * f * wi = 1
+    //
+    // params:
+    //  @eval_points: [P1,...,Pn]. which maps with fixed [Q1,...,Qn]
+    //  @lines: precompute miller lines for fixed [Q1,...,Qn]
+    //  @non_fixed_points: [P'1,...,P'm]. which maps with non-fixed [Q'1,...,Q'm]
+    //  @non_fixed_q: [Q'1,...,Q'm]
+    //  @c: c^lambda = f*w^i
+    //  @wi: w^i, which must be one of w^0, ..., w^{d-1} for the canonical root w, e.g. 1, w and w^2 for BN254.
+    //
+    // return: Ok(false) if the check fails, or the error if the inputs or witness are malformed.
+    pub fn verify_multi_pairing(
+        eval_points: Vec<G1Affine<P>>,
+        lines: &[G2Prepared<P>],
+        non_fixed_points: Vec<G1Affine<P>>,
+        non_fixed_q: Vec<G2Affine<P>>,
+        c: Fp12<P::Fp12Config>,
+        wi: Fp12<P::Fp12Config>,
+    ) -> Result<bool, PairingError> {
+        Self::check_wi(&wi)?;
+        let c_inv = c.inverse().ok_or(PairingError::InvalidWitness)?;
+
+        Self::check(
+            &eval_points,
+            lines,
+            &non_fixed_points,
+            &non_fixed_q,
+            c,
+            c_inv,
+            wi,
+        )
+    }
+
+    // Soundness check: wi must be one of w^0, ..., w^{d-1} for the canonical root w,
+    // e.g. 1, w and w^2 for the fixed cubic non-residue w of order 27 over BN254.
+    //
+    // Otherwise a malicious prover could pick an arbitrary wi, say wi = c^lambda / f,
+    // and absorb any discrepancy into it.
+    pub fn check_wi(wi: &Fp12<P::Fp12Config>) -> Result<u8, PairingError> {
+        P::residue_params()
+            .wi_index(wi)
+            .ok_or(PairingError::InvalidWitness)
+    }
+
+    fn check(
+        eval_points: &[G1Affine<P>],
+        lines: &[G2Prepared<P>],
+        non_fixed_points: &[G1Affine<P>],
+        non_fixed_q: &[G2Affine<P>],
+        c: Fp12<P::Fp12Config>,
+        c_inv: Fp12<P::Fp12Config>,
+        wi: Fp12<P::Fp12Config>,
+    ) -> Result<bool, PairingError> {
+        // 1. final_f = c_inv^{6x+2+p-p^2} * f * wi
+        let final_f = PairingProver::merged_miller_loop(
+            eval_points,
+            lines,
+            non_fixed_points,
+            non_fixed_q,
+            c,
+            c_inv,
+            wi,
+        )?;
+
+        // 2. final_f * c_inv^{p^3}
+        let res = final_f * c_inv.frobenius_map(3);
+
+        // 3. c_inv^lambda * f * wi = 1
+        Ok(res == Fp12::ONE)
+    }
+}
 
-        let hint = if sign {
-            pvk.f * pvk.wi * (pvk.c_inv.pow(exp.to_u64_digits()))
-        } else {
-            pvk.f * pvk.wi * (pvk.c_inv.pow(exp.to_u64_digits()).inverse().unwrap())
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::lambda_residues::LambdaResidues;
+    use crate::params;
+    use crate::utils::{sample_pairing_equation, sample_pairing_equation_on};
+    use ark_bn254::{Bn254, Fq12, G1Affine};
+    use ark_ec::bn::Bn;
+    use ark_ec::pairing::{Pairing, PairingOutput};
+    use ark_ec::AffineRepr;
+    use ark_std::{UniformRand, Zero};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    // A proof of 4 pairings over the curve P, whose d scalars wi are tried by a forged proof.
+    pub(crate) fn test_prove_and_verify_on<P: BnResidueConfig>(d: u64) {
+        let rng = &mut ark_std::test_rng();
+        let (P, Q) = sample_pairing_equation_on::<Bn<P>, _>(4, rng);
+
+        // (Q1,Q2) are fixed, and (Q3,Q4) are non-fixed.
+        let pvk = PairingPVKey::<P>::setup(Q[0..2].to_vec());
+        let proof =
+            PairingProver::<P>::prove(&pvk, P[0..2].to_vec(), P[2..4].to_vec(), Q[2..4].to_vec())
+                .unwrap();
+        assert!((proof.wi_index as u64) < d);
+        let verify = |proof: &PairingProof<Fp12<P::Fp12Config>>| {
+            PairingVerifier::<P>::verify(
+                &pvk,
+                P[0..2].to_vec(),
+                P[2..4].to_vec(),
+                Q[2..4].to_vec(),
+                proof,
+            )
         };
+        assert_eq!(verify(&proof), Ok(true));
+
+        // the other wi
+        let mut forged = proof.clone();
+        forged.wi_index = ((proof.wi_index as u64 + 1) % d) as u8;
+        assert_eq!(verify(&forged), Ok(false));
+
+        // wi out of range
+        forged.wi_index = d as u8;
+        assert_eq!(verify(&forged), Err(PairingError::InvalidWitness));
+
+        // the pairing product isn't 1
+        let res =
+            PairingProver::<P>::prove(&pvk, P[0..2].to_vec(), P[2..4].to_vec(), Q[1..3].to_vec());
+        assert_eq!(res.err(), Some(PairingError::NotRthResidue));
+    }
+
+    #[test]
+    fn test_prove_and_verify() {
+        test_prove_and_verify_on::<ark_bn254::Config>(3);
+    }
+
+    #[test]
+    fn test_verify() {
+        let (P, Q) = sample_pairing_equation(4, &mut ark_std::test_rng());
+
+        // (Q1,Q2,Q3) are fixed, and Q4 is non-fixed.
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..3].to_vec());
+        let proof = PairingProver::prove(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]]).unwrap();
+        assert!(
+            PairingVerifier::verify(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]], &proof)
+                .unwrap()
+        );
+
+        // without hint
+        let proof_without_hint = PairingProof {
+            c_inv: None,
+            ..proof.clone()
+        };
+        assert!(PairingVerifier::verify(
+            &pvk,
+            P[0..3].to_vec(),
+            vec![P[3]],
+            vec![Q[3]],
+            &proof_without_hint
+        )
+        .unwrap());
+
+        // wrong hint
+        let wrong_hint = PairingProof {
+            c_inv: Some(proof.c),
+            ..proof.clone()
+        };
+        let res =
+            PairingVerifier::verify(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]], &wrong_hint);
+        assert_eq!(res, Err(PairingError::InvalidWitness));
+
+        // wrong wi
+        for wi_index in [3, u8::MAX] {
+            let wrong_wi = PairingProof {
+                wi_index,
+                ..proof.clone()
+            };
+            let res =
+                PairingVerifier::verify(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]], &wrong_wi);
+            assert_eq!(res, Err(PairingError::InvalidWitness));
+        }
+        for wi_index in 0..3 {
+            if wi_index == proof.wi_index {
+                continue;
+            }
+            let wrong_wi = PairingProof {
+                wi_index,
+                ..proof.clone()
+            };
+            let res =
+                PairingVerifier::verify(&pvk, P[0..3].to_vec(), vec![P[3]], vec![Q[3]], &wrong_wi);
+            assert_eq!(res, Ok(false));
+        }
+
+        // wrong length
+        let res = PairingVerifier::verify(&pvk, P[0..3].to_vec(), vec![P[3]], vec![], &proof);
+        assert_eq!(res, Err(PairingError::WrongInputLength));
+
+        // wrong statement
+        let P4 = (P[3] + G1Affine::generator()).into();
+        assert!(
+            !PairingVerifier::verify(&pvk, P[0..3].to_vec(), vec![P4], vec![Q[3]], &proof).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_with_many_non_fixed_q() {
+        let (P, Q) = sample_pairing_equation(6, &mut ark_std::test_rng());
+
+        // Q1 is fixed, and (Q2,...,Q6) are non-fixed.
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(vec![Q[0]]);
+        let proof =
+            PairingProver::prove(&pvk, vec![P[0]], P[1..].to_vec(), Q[1..].to_vec()).unwrap();
+        assert!(PairingVerifier::verify(
+            &pvk,
+            vec![P[0]],
+            P[1..].to_vec(),
+            Q[1..].to_vec(),
+            &proof
+        )
+        .unwrap());
+
+        // swap the non-fixed Q2 and Q3
+        let mut swapped = Q[1..].to_vec();
+        swapped.swap(0, 1);
+        assert!(
+            !PairingVerifier::verify(&pvk, vec![P[0]], P[1..].to_vec(), swapped, &proof).unwrap()
+        );
+
+        // e(O,Q) and e(P,O) are skipped
+        let (mut non_fixed_points, mut non_fixed_q) = (P[1..].to_vec(), Q[1..].to_vec());
+        non_fixed_points.extend([G1Affine::zero(), P[0]]);
+        non_fixed_q.extend([Q[0], ark_bn254::G2Affine::zero()]);
+        assert!(PairingVerifier::verify(
+            &pvk,
+            vec![P[0]],
+            non_fixed_points.clone(),
+            non_fixed_q.clone(),
+            &proof
+        )
+        .unwrap());
+
+        // while e(P,Q) isn't
+        non_fixed_points.push(P[0]);
+        non_fixed_q.push(Q[0]);
+        assert!(
+            !PairingVerifier::verify(&pvk, vec![P[0]], non_fixed_points, non_fixed_q, &proof)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_with_target() {
+        let rng = &mut ark_std::test_rng();
+        let (P, Q) = sample_pairing_equation(4, rng);
+
+        // e(P1,Q1) * e(P2,Q2) * e(P3,Q3) = T, where T = e(-P4,Q4)
+        let T = Bn254::pairing(-P[3], Q[3]);
+        let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..2].to_vec());
+        for target in [
+            PairingTarget::new(T).unwrap(),
+            PairingTarget::from_miller_loop(Bn254::miller_loop(-P[3], Q[3]).0).unwrap(),
+        ] {
+            let proof = PairingProver::prove_with_target(
+                &pvk,
+                P[0..2].to_vec(),
+                vec![P[2]],
+                vec![Q[2]],
+                &target,
+            )
+            .unwrap();
+            assert!(PairingVerifier::verify_with_target(
+                &pvk,
+                P[0..2].to_vec(),
+                vec![P[2]],
+                vec![Q[2]],
+                &target,
+                &proof
+            )
+            .unwrap());
+
+            // the proof is bound to T
+            assert!(!PairingVerifier::verify(
+                &pvk,
+                P[0..2].to_vec(),
+                vec![P[2]],
+                vec![Q[2]],
+                &proof
+            )
+            .unwrap());
+        }
+
+        // wrong target
+        let wrong = PairingTarget::new(T + T).unwrap();
+        let res = PairingProver::prove_with_target(
+            &pvk,
+            P[0..2].to_vec(),
+            vec![P[2]],
+            vec![Q[2]],
+            &wrong,
+        );
+        assert_eq!(res, Err(PairingError::NotRthResidue));
+        let proof = PairingProver::prove_with_target(
+            &pvk,
+            P[0..2].to_vec(),
+            vec![P[2]],
+            vec![Q[2]],
+            &PairingTarget::new(T).unwrap(),
+        )
+        .unwrap();
+        assert!(!PairingVerifier::verify_with_target(
+            &pvk,
+            P[0..2].to_vec(),
+            vec![P[2]],
+            vec![Q[2]],
+            &wrong,
+            &proof
+        )
+        .unwrap());
+
+        // T = 1 is the same as `verify`
+        let proof =
+            PairingProver::prove(&pvk, P[0..2].to_vec(), P[2..].to_vec(), Q[2..].to_vec()).unwrap();
+        let one = PairingTarget::new(PairingOutput::<Bn254>::zero()).unwrap();
+        assert!(PairingVerifier::verify_with_target(
+            &pvk,
+            P[0..2].to_vec(),
+            P[2..].to_vec(),
+            Q[2..].to_vec(),
+            &one,
+            &proof
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn test_verify_multi_pairing() {
+        let (P, Q) = sample_pairing_equation(4, &mut ark_std::test_rng());
+        let f = Bn254::multi_miller_loop(P.clone(), Q.clone()).0;
+        let witness = LambdaResidues::finding_c(f).unwrap();
+
+        // (Q1,Q2,Q3) are fixed, and Q4 is non-fixed.
+        let lines = Q[0..3]
+            .iter()
+            .map(|q| q.into())
+            .collect::<Vec<G2Prepared<ark_bn254::Config>>>();
+        assert!(PairingVerifier::verify_multi_pairing(
+            P[0..3].to_vec(),
+            &lines,
+            vec![P[3]],
+            vec![Q[3]],
+            witness.c,
+            witness.wi,
+        )
+        .unwrap());
+
+        // wrong witness
+        assert!(!PairingVerifier::verify_multi_pairing(
+            P[0..3].to_vec(),
+            &lines,
+            vec![P[3]],
+            vec![Q[3]],
+            witness.c.square(),
+            witness.wi,
+        )
+        .unwrap());
+        let res = PairingVerifier::verify_multi_pairing(
+            P[0..3].to_vec(),
+            &lines,
+            vec![P[3]],
+            vec![Q[3]],
+            witness.c,
+            witness.wi.square(),
+        );
+        assert!(res == Ok(false) || res == Err(PairingError::InvalidWitness));
+        let res = PairingVerifier::verify_multi_pairing(
+            P[0..3].to_vec(),
+            &lines,
+            vec![P[3]],
+            vec![Q[3]],
+            Fq12::ZERO,
+            witness.wi,
+        );
+        assert_eq!(res, Err(PairingError::InvalidWitness));
+    }
+
+    #[test]
+    fn test_verify_for_each_wi() {
+        let exp = params::MODULUS.pow(12) - 1_u32;
+        let cofactor_cubic = exp / 3_u32;
+
+        // sample pairings until f falls into each class of cubic residue, namely wi = 1, w and w^2.
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let mut wis: Vec<Fq12> = vec![];
+        while wis.len() < 3 {
+            let (P, Q) = sample_pairing_equation(3, &mut prng);
+            let pvk = PairingPVKey::<ark_bn254::Config>::setup(Q[0..2].to_vec());
+            let proof =
+                PairingProver::prove(&pvk, P[0..2].to_vec(), vec![P[2]], vec![Q[2]]).unwrap();
+            let wi = params::WI[proof.wi_index as usize];
+            if wis.contains(&wi) {
+                continue;
+            }
+
+            let f = Bn254::multi_miller_loop(P.clone(), Q.clone()).0;
+            let is_cubic_residue = f.pow(cofactor_cubic.to_u64_digits()) == Fq12::ONE;
+            assert_eq!(proof.wi_index == 0, is_cubic_residue);
+
+            assert!(PairingVerifier::verify(
+                &pvk,
+                P[0..2].to_vec(),
+                vec![P[2]],
+                vec![Q[2]],
+                &proof
+            )
+            .unwrap());
+            wis.push(wi);
+        }
+        assert!(wis.contains(&Fq12::ONE));
+    }
+
+    #[test]
+    fn test_verify_with_forged_wi() {
+        let rng = &mut ark_std::test_rng();
+        let (P, Q) = sample_pairing_equation(4, rng);
+        let lines = Q[0..3]
+            .iter()
+            .map(|q| q.into())
+            .collect::<Vec<G2Prepared<ark_bn254::Config>>>();
+
+        let f = Bn254::multi_miller_loop(P.clone(), Q.clone()).0;
+        let witness = LambdaResidues::finding_c(f).unwrap();
+        let verify = |c: Fq12, wi: Fq12, P4: G1Affine| {
+            PairingVerifier::verify_multi_pairing(
+                P[0..3].to_vec(),
+                &lines,
+                vec![P4],
+                vec![Q[3]],
+                c,
+                wi,
+            )
+        };
+        assert_eq!(verify(witness.c, witness.wi, P[3]), Ok(true));
+
+        // 1. random wi
+        for _ in 0..10 {
+            let wi = Fq12::rand(rng);
+            assert_eq!(
+                verify(witness.c, wi, P[3]),
+                Err(PairingError::InvalidWitness)
+            );
+        }
+
+        // 2. the other 27-th roots of unity, which are not allowed.
+        for i in 3..27 {
+            let wi = params::W.pow([i as u64]);
+            assert_eq!(
+                verify(witness.c, wi, P[3]),
+                Err(PairingError::InvalidWitness)
+            );
+        }
+
+        // 3. scale the allowed wi by a random r-th residue.
+        let y = Fq12::rand(rng).pow(params::R.to_u64_digits());
+        assert_eq!(
+            verify(witness.c, witness.wi * y, P[3]),
+            Err(PairingError::InvalidWitness)
+        );
 
-        let p_pow3 = params::MODULUS.pow(3_u32);
-        assert_eq!(hint, pvk.c.pow(p_pow3.to_u64_digits()), "hint is wrong");
-        // assert_eq!(final_f, hint, "final_f not equal hint");
-        final_f == hint
+        // 4. forge wi = c^lambda / f to absorb the discrepancy of a wrong statement.
+        let P4: G1Affine = (P[3] + G1Affine::generator()).into();
+        let wrong_f = Bn254::multi_miller_loop(P[0..3].iter().copied().chain([P4]), Q.clone()).0;
+        let c = Fq12::rand(rng);
+        let forged_wi = c.pow(params::LAMBDA.to_u64_digits()) * wrong_f.inverse().unwrap();
+        // the forged wi passes the pairing check...
+        let c_inv = c.inverse().unwrap();
+        let res = PairingVerifier::check(&P[0..3], &lines, &[P4], &Q[3..4], c, c_inv, forged_wi);
+        assert_eq!(res, Ok(true));
+        // ...but it's rejected by the soundness check of wi.
+        assert_eq!(verify(c, forged_wi, P4), Err(PairingError::InvalidWitness));
     }
 }